@@ -0,0 +1,91 @@
+//! The shapes of `/move` responses each Battlesnake API version allows, checked with `--api-version`.
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiVersion {
+    /// `move` is required and a string, `shout` and `latency` are optional
+    V1,
+}
+
+impl FromStr for ApiVersion {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "1" => Ok(ApiVersion::V1),
+            _ => Err(anyhow!("Unknown API version \"{}\", expected 1", s)),
+        }
+    }
+}
+
+impl ApiVersion {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "1",
+        }
+    }
+
+    /// Fails with the first field of the response that doesn't have the type the version requires
+    ///
+    /// The move is looked for under `move_key`, so `--move-key` still works with a version selected.
+    pub fn validate(self, response: &Value, move_key: &str) -> Result<()> {
+        let fields = response.as_object().ok_or_else(|| {
+            anyhow!(
+                "The response is {}, API version 1 requires an object",
+                type_name(response)
+            )
+        })?;
+
+        match fields.get(move_key) {
+            Some(Value::String(_)) => {}
+            Some(other) => {
+                return Err(anyhow!(
+                    "\"{}\" is {}, API version 1 requires a string",
+                    move_key,
+                    type_name(other)
+                ))
+            }
+            None => {
+                return Err(anyhow!(
+                    "\"{}\" is missing, API version 1 requires it",
+                    move_key
+                ))
+            }
+        }
+        match fields.get("shout") {
+            None | Some(Value::Null) | Some(Value::String(_)) => {}
+            Some(other) => {
+                return Err(anyhow!(
+                    "\"shout\" is {}, API version 1 allows only a string",
+                    type_name(other)
+                ))
+            }
+        }
+        match fields.get("latency") {
+            None | Some(Value::Null) | Some(Value::String(_)) | Some(Value::Number(_)) => {}
+            Some(other) => {
+                return Err(anyhow!(
+                    "\"latency\" is {}, API version 1 allows only a string or a number",
+                    type_name(other)
+                ))
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
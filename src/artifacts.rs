@@ -0,0 +1,86 @@
+//! `--artifacts-dir`, a directory per failed test with what was sent, what came back and the
+//! board, so a failure can be looked into without recreating the request by hand.
+
+use std::{
+    fs::{create_dir_all, write},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+
+use crate::board::GameState;
+use crate::client::SnakeClient;
+use crate::{repro, Args, TestRun};
+
+/// Writes `request.json`, `response.json` when there was one, `board.txt` and `failure.txt`
+/// for every failed test
+///
+/// Each test gets a directory named after its path, with the characters that can't be in a
+/// file name replaced, and files left from an earlier run are overwritten.
+pub fn write_artifacts(
+    args: &Args,
+    client: &SnakeClient,
+    dir: &Path,
+    results: &[TestRun],
+) -> Result<()> {
+    let colorized = colored::control::SHOULD_COLORIZE.should_colorize();
+    colored::control::set_override(false);
+    let written = results
+        .iter()
+        .filter(|r| r.result.is_err())
+        .try_for_each(|r| write_failure(args, client, dir, r));
+    colored::control::set_override(colorized);
+    written
+}
+
+fn write_failure(args: &Args, client: &SnakeClient, dir: &Path, r: &TestRun) -> Result<()> {
+    let test_case = &r.test_case;
+    let name: String = test_case
+        .path
+        .display()
+        .to_string()
+        .trim_start_matches("./")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let dir = dir.join(name);
+    create_dir_all(&dir).with_context(|| format!("Could not create {}", dir.display()))?;
+
+    let body = client.request_body(&test_case.state);
+    write(
+        dir.join("request.json"),
+        serde_json::to_string_pretty(&body)? + "\n",
+    )?;
+    if let Some(response) = &r.response {
+        write(
+            dir.join("response.json"),
+            serde_json::to_string_pretty(response)? + "\n",
+        )?;
+    }
+    let board = match GameState::from_value(&test_case.state) {
+        Ok(state) => state.render(),
+        Err(e) => format!("The state could not be drawn: {}\n", e),
+    };
+    write(dir.join("board.txt"), board)?;
+
+    let failure = match &r.result {
+        Err(failure) => failure.display_failure(args),
+        Ok(()) => return Ok(()),
+    };
+    write(
+        dir.join("failure.txt"),
+        format!(
+            "{}\n{}\nReproduce with: {}\n",
+            test_case.path.display(),
+            failure,
+            repro::repro_command(args, test_case)
+        ),
+    )?;
+    Ok(())
+}
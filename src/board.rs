@@ -0,0 +1,783 @@
+//! Typed view of the Battlesnake game state that gets sent to the snake.
+//!
+//! Test files keep their `state` as raw JSON so that it is forwarded to the
+//! server untouched. These types are only used when the tool itself needs to
+//! reason about the board. Any fields we don't model are kept in `extra` so a
+//! state can be round-tripped without losing information.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    str::FromStr,
+};
+
+use anyhow::anyhow;
+use colored::{Color, Colorize};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    /// Other spellings that parse as a direction, there are none yet so only the names in `ALL` parse
+    pub const ALIASES: &'static [(&'static str, Direction)] = &[];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Direction::Up => "up",
+            Direction::Down => "down",
+            Direction::Left => "left",
+            Direction::Right => "right",
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Direction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "up" => Ok(Direction::Up),
+            "down" => Ok(Direction::Down),
+            "left" => Ok(Direction::Left),
+            "right" => Ok(Direction::Right),
+            _ => Direction::ALIASES
+                .iter()
+                .find(|(alias, _)| *alias == s)
+                .map(|(_, direction)| *direction)
+                .ok_or_else(|| anyhow!("\"{}\" is not a valid direction", s)),
+        }
+    }
+}
+
+/// A board's dimensions, written `WxH` on the command line, ie `7x7`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoardSize {
+    pub width: i32,
+    pub height: i32,
+}
+
+impl BoardSize {
+    /// Whether both dimensions are at least as large as the other's
+    pub fn at_least(self, other: BoardSize) -> bool {
+        self.width >= other.width && self.height >= other.height
+    }
+}
+
+impl fmt::Display for BoardSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)
+    }
+}
+
+impl FromStr for BoardSize {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            anyhow!(
+                "\"{}\" is not a board size, expected WIDTHxHEIGHT, ie 11x11",
+                s
+            )
+        };
+        let (width, height) = s.split_once('x').ok_or_else(invalid)?;
+        Ok(BoardSize {
+            width: width.trim().parse().map_err(|_| invalid())?,
+            height: height.trim().parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Coord {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Coord {
+    /// The neighbouring coordinate in the given direction, `up` increases `y`
+    pub fn moved(self, direction: Direction) -> Coord {
+        match direction {
+            Direction::Up => Coord {
+                x: self.x,
+                y: self.y + 1,
+            },
+            Direction::Down => Coord {
+                x: self.x,
+                y: self.y - 1,
+            },
+            Direction::Left => Coord {
+                x: self.x - 1,
+                y: self.y,
+            },
+            Direction::Right => Coord {
+                x: self.x + 1,
+                y: self.y,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GameState {
+    pub game: Game,
+    pub turn: u32,
+    pub board: Board,
+    pub you: Snake,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// How coordinates move on a board, which depends on the ruleset
+///
+/// In the `wrapped` ruleset moving off one edge enters the board again from the
+/// opposite edge, so there are no walls.
+#[derive(Debug, Clone, Copy)]
+pub struct Geometry {
+    pub width: i32,
+    pub height: i32,
+    pub wrapped: bool,
+}
+
+impl Geometry {
+    /// The neighbouring coordinate in the given direction, wrapped around the edges when the ruleset does
+    pub fn step(self, c: Coord, direction: Direction) -> Coord {
+        let next = c.moved(direction);
+        if !self.wrapped {
+            return next;
+        }
+        Coord {
+            x: next.x.rem_euclid(self.width),
+            y: next.y.rem_euclid(self.height),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Game {
+    pub id: String,
+    #[serde(default)]
+    pub ruleset: Ruleset,
+    #[serde(default)]
+    pub timeout: u32,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Game {
+    /// The health a snake loses on a hazard on top of the usual 1, from the ruleset's settings
+    pub fn hazard_damage(&self) -> i32 {
+        self.ruleset
+            .extra
+            .get("settings")
+            .and_then(|s| s["hazardDamagePerTurn"].as_i64())
+            .unwrap_or(14) as i32
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct Ruleset {
+    #[serde(default)]
+    pub name: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Board {
+    pub width: i32,
+    pub height: i32,
+    #[serde(default)]
+    pub food: Vec<Coord>,
+    #[serde(default)]
+    pub hazards: Vec<Coord>,
+    pub snakes: Vec<Snake>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Snake {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    pub health: i32,
+    pub body: Vec<Coord>,
+    pub head: Coord,
+    #[serde(default)]
+    pub length: usize,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl GameState {
+    pub fn from_value(state: &Value) -> serde_json::Result<Self> {
+        serde_json::from_value(state.clone())
+    }
+
+    pub fn to_value(&self) -> serde_json::Result<Value> {
+        serde_json::to_value(self)
+    }
+
+    /// The same state as seen by another snake on the board
+    pub fn as_seen_by(&self, snake: &Snake) -> GameState {
+        GameState {
+            you: snake.clone(),
+            ..self.clone()
+        }
+    }
+
+    pub fn geometry(&self) -> Geometry {
+        Geometry {
+            width: self.board.width,
+            height: self.board.height,
+            wrapped: self.game.ruleset.name == "wrapped",
+        }
+    }
+
+    pub fn is_alive(&self, snake_id: &str) -> bool {
+        self.board.snakes.iter().any(|s| s.id == snake_id)
+    }
+
+    /// Advances the game by one turn using the standard rules, or the wrapped or constrictor ones
+    ///
+    /// Snakes without an entry in `moves` keep moving in the direction they are facing.
+    /// In `constrictor` every snake grows each turn and stays at full health. A snake that ends
+    /// up on a hazard without food takes the hazard damage, like in `royale`.
+    /// No new food is spawned and hazards don't spread, so the outcome only depends on the moves
+    /// given.
+    pub fn advance(&mut self, moves: &HashMap<String, Direction>) {
+        let geometry = self.geometry();
+        let constrictor = self.game.ruleset.name == "constrictor";
+        let hazard_damage = self.game.hazard_damage();
+        let Board {
+            snakes,
+            food,
+            hazards,
+            ..
+        } = &mut self.board;
+
+        for snake in snakes.iter_mut() {
+            let direction = moves
+                .get(&snake.id)
+                .copied()
+                .or_else(|| snake.facing(geometry))
+                .unwrap_or(Direction::Up);
+            snake.head = geometry.step(snake.head, direction);
+            snake.body.insert(0, snake.head);
+            if constrictor {
+                snake.health = 100;
+            } else {
+                snake.body.pop();
+                snake.health -= 1;
+            }
+            if hazards.contains(&snake.head) && !food.contains(&snake.head) {
+                snake.health -= hazard_damage;
+            }
+        }
+
+        let mut eaten = vec![];
+        for snake in snakes.iter_mut() {
+            if food.contains(&snake.head) {
+                snake.health = 100;
+                // A snake without a body only grows its head back
+                let tail = snake.body.last().copied().unwrap_or(snake.head);
+                snake.body.push(tail);
+                eaten.push(snake.head);
+            }
+            snake.length = snake.body.len();
+        }
+        food.retain(|f| !eaten.contains(f));
+
+        let eliminated: Vec<String> = self
+            .board
+            .snakes
+            .iter()
+            .filter(|s| self.board.is_eliminated(s))
+            .map(|s| s.id.clone())
+            .collect();
+        self.board.snakes.retain(|s| !eliminated.contains(&s.id));
+
+        if let Some(you) = self.board.snakes.iter().find(|s| s.id == self.you.id) {
+            self.you = you.clone();
+        }
+        self.turn += 1;
+    }
+}
+
+impl GameState {
+    /// Draws the board as text, with `y` increasing upwards like the engine does
+    ///
+    /// "you" are drawn with `Y` for the head and `y` for the body, opponents use
+    /// `A`/`a`, `B`/`b` and so on. Food is `*` and hazards are `#`.
+    pub fn render(&self) -> String {
+        let board = &self.board;
+        let mut out = String::new();
+
+        for y in (0..board.height).rev() {
+            for x in 0..board.width {
+                out.push(self.cell_char(Coord { x, y }));
+                if x + 1 < board.width {
+                    out.push(' ');
+                }
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// Like `render`, with the target cell drawn by `highlight`, ie in a color
+    ///
+    /// When the target is off the board, the row or column of blank cells just past that edge is
+    /// added and the target is drawn in it as `X`.
+    pub fn render_with_target(&self, target: Coord, highlight: impl Fn(char) -> String) -> String {
+        let board = &self.board;
+        let margin = if board.contains(target) { 0 } else { 1 };
+        let mut out = String::new();
+
+        for y in (-margin..board.height + margin).rev() {
+            let row: Vec<_> = (-margin..board.width + margin)
+                .map(|x| {
+                    let c = Coord { x, y };
+                    let cell = if board.contains(c) {
+                        self.cell_char(c)
+                    } else if c == target {
+                        'X'
+                    } else {
+                        ' '
+                    };
+                    if c == target {
+                        highlight(cell)
+                    } else {
+                        cell.to_string()
+                    }
+                })
+                .collect();
+            let row = row.join(" ");
+            // Only the frame row the target is in is drawn
+            if !row.trim().is_empty() {
+                out += row.trim_end();
+                out.push('\n');
+            }
+        }
+
+        out
+    }
+
+    /// Like `render`, in color, with every head drawn as an arrow pointing the way it last moved
+    ///
+    /// Each snake has its own color, "you" are green. Food is red and hazards are dimmed. A head
+    /// that hasn't moved yet keeps its letter. The board is followed by a legend of the snakes.
+    pub fn render_colored(&self) -> String {
+        const COLORS: [Color; 6] = [
+            Color::Blue,
+            Color::Magenta,
+            Color::Cyan,
+            Color::Yellow,
+            Color::BrightBlue,
+            Color::BrightMagenta,
+        ];
+        let opponents = self.board.snakes.iter().filter(|s| s.id != self.you.id);
+        let snakes: Vec<_> = std::iter::once(('y', &self.you, Color::Green))
+            .chain(
+                opponents
+                    .zip("abcdefghijklmnopqrstuvwx".chars())
+                    .zip(COLORS.iter().cycle())
+                    .map(|((s, l), c)| (l, s, *c)),
+            )
+            .collect();
+        let geometry = self.geometry();
+        let heading = |snake: &Snake| {
+            let neck = *snake.body.get(1)?;
+            Direction::ALL
+                .iter()
+                .copied()
+                .find(|d| neck != snake.head && geometry.step(neck, *d) == snake.head)
+        };
+
+        let board = &self.board;
+        let mut out = String::new();
+        for y in (0..board.height).rev() {
+            let row: Vec<_> = (0..board.width)
+                .map(|x| {
+                    let c = Coord { x, y };
+                    for (letter, snake, color) in &snakes {
+                        if snake.head == c {
+                            let head = match heading(snake) {
+                                Some(Direction::Up) => '^',
+                                Some(Direction::Down) => 'v',
+                                Some(Direction::Left) => '<',
+                                Some(Direction::Right) => '>',
+                                None => letter.to_ascii_uppercase(),
+                            };
+                            return head.to_string().color(*color).bold().to_string();
+                        }
+                        if snake.body.contains(&c) {
+                            return letter.to_string().color(*color).to_string();
+                        }
+                    }
+                    match self.cell_char(c) {
+                        '*' => "*".red().to_string(),
+                        '#' => "#".bright_black().to_string(),
+                        other => other.to_string(),
+                    }
+                })
+                .collect();
+            out += &row.join(" ");
+            out.push('\n');
+        }
+
+        for (letter, snake, color) in &snakes {
+            let label = format!("{}: \"{}\"", letter, snake.name).color(*color);
+            out += &format!(
+                "{}, length {}, health {}\n",
+                label,
+                snake.body.len(),
+                snake.health
+            );
+        }
+        out
+    }
+
+    /// A one line synopsis of the state, ie `11x11 board, turn 3, you: length 5 health 90, 2 opponents, 4 food`
+    pub fn describe(&self) -> String {
+        let opponents = self
+            .board
+            .snakes
+            .iter()
+            .filter(|s| s.id != self.you.id)
+            .count();
+        let mut description = format!(
+            "{}x{} board, turn {}, you: length {} health {}, {} opponents, {} food",
+            self.board.width,
+            self.board.height,
+            self.turn,
+            self.you.body.len(),
+            self.you.health,
+            opponents,
+            self.board.food.len(),
+        );
+        if !self.board.hazards.is_empty() {
+            description += &format!(", {} hazards", self.board.hazards.len());
+        }
+        description
+    }
+
+    fn cell_char(&self, c: Coord) -> char {
+        let opponents = self.board.snakes.iter().filter(|s| s.id != self.you.id);
+        let snakes = std::iter::once(('y', &self.you)).chain(
+            opponents
+                .zip("abcdefghijklmnopqrstuvwx".chars())
+                .map(|(s, l)| (l, s)),
+        );
+
+        for (letter, snake) in snakes {
+            if snake.head == c {
+                return letter.to_ascii_uppercase();
+            }
+            if snake.body.contains(&c) {
+                return letter;
+            }
+        }
+        if self.board.food.contains(&c) {
+            return '*';
+        }
+        if self.board.hazards.contains(&c) {
+            return '#';
+        }
+        '.'
+    }
+}
+
+impl GameState {
+    /// The Manhattan distance from the cell to the nearest food, `None` without any food
+    pub fn food_distance(&self, c: Coord) -> Option<i32> {
+        self.board
+            .food
+            .iter()
+            .map(|f| (c.x - f.x).abs() + (c.y - f.y).abs())
+            .min()
+    }
+
+    /// Opponents at least as long as "you" whose head can also reach where the given move goes
+    pub fn losing_head_to_heads(&self, direction: Direction) -> Vec<&Snake> {
+        let geometry = self.geometry();
+        let target = geometry.step(self.you.head, direction);
+        self.board
+            .snakes
+            .iter()
+            .filter(|s| s.id != self.you.id && s.body.len() >= self.you.body.len())
+            .filter(|s| {
+                Direction::ALL
+                    .iter()
+                    .any(|d| geometry.step(s.head, *d) == target)
+            })
+            .collect()
+    }
+
+    /// Moves that keep "you" on the board and out of every snake's body, tails excepted
+    pub fn safe_moves(&self) -> Vec<Direction> {
+        Direction::ALL
+            .iter()
+            .copied()
+            .filter(|d| {
+                let target = self.geometry().step(self.you.head, *d);
+                self.board.contains(target)
+                    && !self
+                        .board
+                        .snakes
+                        .iter()
+                        .any(|s| s.body[..s.body.len().saturating_sub(1)].contains(&target))
+            })
+            .collect()
+    }
+
+    /// What would kill "you" straight away after the move, ie `a wall`, if anything would
+    ///
+    /// Tails don't count as they move out of the way. A hazard is only fatal when the damage
+    /// it does takes the last of the snake's health and the cell has no food to restore it.
+    pub fn fatal_move(&self, direction: Direction) -> Option<String> {
+        let target = self.geometry().step(self.you.head, direction);
+        if !self.board.contains(target) {
+            return Some("a wall".to_owned());
+        }
+        let hit = self
+            .board
+            .snakes
+            .iter()
+            .find(|s| s.body[..s.body.len().saturating_sub(1)].contains(&target));
+        if let Some(snake) = hit {
+            return Some(if snake.id == self.you.id {
+                "its own body".to_owned()
+            } else {
+                format!("the body of \"{}\"", snake.name)
+            });
+        }
+        let hazard_damage = self.game.hazard_damage();
+        if self.board.hazards.contains(&target)
+            && !self.board.food.contains(&target)
+            && self.you.health - 1 - hazard_damage <= 0
+        {
+            return Some(format!(
+                "a hazard, which does {} damage to a snake with {} health",
+                hazard_damage, self.you.health
+            ));
+        }
+        None
+    }
+
+    /// How many cells "you" could reach after making the given move, found by flood fill
+    ///
+    /// Snake bodies block the fill, except for tails which move out of the way.
+    /// Where opponents move next is unknown, so their heads are left where they are.
+    pub fn reachable_space(&self, direction: Direction) -> usize {
+        let geometry = self.geometry();
+        let start = geometry.step(self.you.head, direction);
+        let blocked: HashSet<Coord> = self
+            .board
+            .snakes
+            .iter()
+            .flat_map(|s| &s.body[..s.body.len().saturating_sub(1)])
+            .copied()
+            .collect();
+        if !self.board.contains(start) || blocked.contains(&start) {
+            return 0;
+        }
+
+        let mut seen = HashSet::new();
+        seen.insert(start);
+        let mut frontier = vec![start];
+        while let Some(c) = frontier.pop() {
+            for d in Direction::ALL {
+                let next = geometry.step(c, d);
+                if self.board.contains(next) && !blocked.contains(&next) && seen.insert(next) {
+                    frontier.push(next);
+                }
+            }
+        }
+
+        seen.len()
+    }
+}
+
+impl Board {
+    pub fn contains(&self, c: Coord) -> bool {
+        c.x >= 0 && c.y >= 0 && c.x < self.width && c.y < self.height
+    }
+
+    /// Whether the snake dies at the end of the turn, after every snake has moved and eaten
+    fn is_eliminated(&self, snake: &Snake) -> bool {
+        if snake.health <= 0 || !self.contains(snake.head) {
+            return true;
+        }
+
+        self.snakes.iter().any(|other| {
+            let body_collision = other.body.iter().skip(1).any(|c| *c == snake.head);
+            let lost_head_to_head = other.id != snake.id
+                && other.head == snake.head
+                && other.body.len() >= snake.body.len();
+            body_collision || lost_head_to_head
+        })
+    }
+}
+
+impl Snake {
+    /// The direction the snake moved last turn, if it has a neck to tell from
+    pub fn facing(&self, geometry: Geometry) -> Option<Direction> {
+        let neck = *self.body.get(1)?;
+        Direction::ALL
+            .iter()
+            .copied()
+            .find(|d| geometry.step(neck, *d) == self.head)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// A lone snake of length 3 on an 11x11 board, its body trailing away from `direction`
+    fn state(ruleset: &str, head: Coord, direction: Direction) -> GameState {
+        let opposite = match direction {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        };
+        let neck = head.moved(opposite);
+        let tail = neck.moved(opposite);
+        let snake = json!({
+            "id": "you",
+            "health": 100,
+            "body": [head, neck, tail],
+            "head": head,
+        });
+
+        GameState::from_value(&json!({
+            "game": { "id": "game", "ruleset": { "name": ruleset } },
+            "turn": 0,
+            "board": { "width": 11, "height": 11, "snakes": [snake] },
+            "you": snake,
+        }))
+        .unwrap()
+    }
+
+    fn advance(state: &mut GameState, direction: Direction) {
+        let moves = std::iter::once(("you".to_owned(), direction)).collect();
+        state.advance(&moves);
+    }
+
+    #[test]
+    fn wrapped_heads_cross_every_edge() {
+        let cases = [
+            (Coord { x: 5, y: 10 }, Direction::Up, Coord { x: 5, y: 0 }),
+            (Coord { x: 5, y: 0 }, Direction::Down, Coord { x: 5, y: 10 }),
+            (Coord { x: 0, y: 5 }, Direction::Left, Coord { x: 10, y: 5 }),
+            (
+                Coord { x: 10, y: 5 },
+                Direction::Right,
+                Coord { x: 0, y: 5 },
+            ),
+        ];
+
+        for (head, direction, expected) in cases {
+            let mut state = state("wrapped", head, direction);
+            assert!(state.safe_moves().contains(&direction));
+
+            advance(&mut state, direction);
+            assert!(
+                state.is_alive("you"),
+                "died moving {} from {:?}",
+                direction,
+                head
+            );
+            assert_eq!(state.you.head, expected);
+            assert_eq!(state.you.facing(state.geometry()), Some(direction));
+        }
+    }
+
+    #[test]
+    fn constrictor_snakes_grow_every_turn() {
+        let mut state = state("constrictor", Coord { x: 5, y: 5 }, Direction::Up);
+        state.board.snakes[0].health = 50;
+
+        advance(&mut state, Direction::Up);
+        advance(&mut state, Direction::Left);
+        assert_eq!(state.you.length, 5);
+        assert_eq!(state.you.health, 100);
+
+        advance(&mut state, Direction::Down);
+        advance(&mut state, Direction::Right);
+        assert!(!state.is_alive("you"));
+    }
+
+    #[test]
+    fn snakes_without_a_body_can_eat() {
+        let mut state = state("standard", Coord { x: 5, y: 5 }, Direction::Up);
+        state.board.snakes[0].body.clear();
+        state.board.food = vec![Coord { x: 5, y: 6 }];
+
+        advance(&mut state, Direction::Up);
+        assert_eq!(state.you.health, 100);
+        assert!(state.board.food.is_empty());
+    }
+
+    #[test]
+    fn royale_hazards_damage_snakes_without_food() {
+        let mut state = state("royale", Coord { x: 5, y: 5 }, Direction::Up);
+        state.board.hazards = vec![Coord { x: 5, y: 6 }, Coord { x: 5, y: 7 }];
+        state.board.food = vec![Coord { x: 5, y: 7 }];
+
+        advance(&mut state, Direction::Up);
+        assert_eq!(state.you.health, 100 - 1 - 14);
+
+        advance(&mut state, Direction::Up);
+        assert_eq!(state.you.health, 100);
+    }
+
+    #[test]
+    fn standard_heads_die_at_every_edge() {
+        let cases = [
+            (Coord { x: 5, y: 10 }, Direction::Up),
+            (Coord { x: 5, y: 0 }, Direction::Down),
+            (Coord { x: 0, y: 5 }, Direction::Left),
+            (Coord { x: 10, y: 5 }, Direction::Right),
+        ];
+
+        for (head, direction) in cases {
+            let mut state = state("standard", head, direction);
+            assert!(!state.safe_moves().contains(&direction));
+
+            advance(&mut state, direction);
+            assert!(!state.is_alive("you"));
+        }
+    }
+}
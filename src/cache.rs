@@ -0,0 +1,64 @@
+//! An opt-in on-disk cache of server responses, enabled with `--cache <dir>`.
+//!
+//! Responses are keyed by a hash of the URL and the (key sorted) request body,
+//! so re-running an unchanged test skips the HTTP call. This is only safe for
+//! deterministic snakes, which is why it is off by default.
+
+use std::{
+    fs::{create_dir_all, read_to_string, write},
+    path::PathBuf,
+};
+
+use anyhow::Result;
+use log::debug;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+pub struct ResponseCache {
+    dir: PathBuf,
+    /// Ignore cached responses, but still store the fresh ones
+    refresh: bool,
+    /// Read cached responses without storing new ones
+    read_only: bool,
+}
+
+impl ResponseCache {
+    pub fn new(dir: PathBuf, refresh: bool, read_only: bool) -> Self {
+        ResponseCache {
+            dir,
+            refresh,
+            read_only,
+        }
+    }
+
+    fn path(&self, url: &str, body: &Value) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(url.as_bytes());
+        hasher.update(b"\n");
+        hasher.update(body.to_string().as_bytes());
+        let key: String = hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        self.dir.join(format!("{}.json", key))
+    }
+
+    pub fn get(&self, url: &str, body: &Value) -> Option<String> {
+        if self.refresh {
+            return None;
+        }
+        read_to_string(self.path(url, body)).ok()
+    }
+
+    pub fn put(&self, url: &str, body: &Value, response: &str) -> Result<()> {
+        if self.read_only {
+            return Ok(());
+        }
+        create_dir_all(&self.dir)?;
+        let path = self.path(url, body);
+        debug!("Caching the response for {} in {}", url, path.display());
+        write(path, response)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+//! The `capture` subcommand, which bootstraps a new test file from a bare state by
+//! recording the move the server makes for it today.
+
+use std::{fs::read_to_string, path::Path};
+
+use anyhow::{anyhow, Result};
+
+use crate::client::SnakeClient;
+use crate::format::write_test_case_file;
+use crate::stale;
+use crate::{Args, TestCaseFile};
+
+pub fn capture(args: &Args, url: &str, state: &Path, output: &Path) -> Result<()> {
+    if output.exists() {
+        return Err(anyhow!(
+            "{} already exists, use --record-interactive to update existing tests",
+            output.display()
+        ));
+    }
+
+    let state: serde_json::Value = serde_json::from_str(&read_to_string(state)?)?;
+    let response = SnakeClient::new(args)?.request_move(url, &state)?;
+
+    let test_case_file = TestCaseFile {
+        state_hash: Some(stale::state_hash(&state)),
+        state,
+        expected: vec![response.r#move.clone().into()],
+        description: Some("TODO: describe what this test checks".to_owned()),
+        ..Default::default()
+    };
+    write_test_case_file(output, &test_case_file)?;
+
+    println!(
+        "Wrote {} expecting \"{}\", check the move and fill in the description",
+        output.display(),
+        response.r#move
+    );
+    Ok(())
+}
@@ -0,0 +1,77 @@
+//! Checks of the server's response and chosen move, independent of `expected`.
+//!
+//! These encode general tactical rules and produce warnings, which `--strict`
+//! turns into failures.
+
+use crate::board::{Direction, GameState};
+use crate::{Args, BattlesnakeMoveResponse, TestCase};
+
+pub fn move_warnings(args: &Args, test_case: &TestCase, actual_move: &str) -> Vec<String> {
+    let mut warnings = vec![];
+
+    let (state, direction) = match (
+        GameState::from_value(&test_case.state),
+        actual_move.parse::<Direction>(),
+    ) {
+        (Ok(state), Ok(direction)) => (state, direction),
+        _ => return warnings,
+    };
+
+    if args.check_headtohead || test_case.check_headtohead {
+        let target = state.geometry().step(state.you.head, direction);
+        for opponent in state.losing_head_to_heads(direction) {
+            warnings.push(format!(
+                "Moving {} to ({}, {}) risks a losing head-to-head with \"{}\" (length {} vs {})",
+                direction,
+                target.x,
+                target.y,
+                opponent.name,
+                opponent.body.len(),
+                state.you.body.len()
+            ));
+        }
+    }
+
+    if let Some(min_space) = test_case.min_space {
+        let space = state.reachable_space(direction);
+        if space < min_space {
+            warnings.push(format!(
+                "Moving {} leaves only {} reachable cells, fewer than the min_space of {}",
+                direction, space, min_space
+            ));
+        }
+    }
+
+    warnings
+}
+
+/// What the move runs into and the moves that wouldn't have, for `--check-legal`
+///
+/// A move is only reported when another one was safe, a snake that's trapped loses whatever it
+/// does. States that can't be parsed as a game state aren't checked.
+pub fn fatal_move(test_case: &TestCase, actual_move: &str) -> Option<(String, Vec<String>)> {
+    let state = GameState::from_value(&test_case.state).ok()?;
+    let cause = state.fatal_move(actual_move.parse().ok()?)?;
+    let safe: Vec<_> = Direction::ALL
+        .iter()
+        .filter(|d| state.fatal_move(**d).is_none())
+        .map(|d| d.to_string())
+        .collect();
+    (!safe.is_empty()).then_some((cause, safe))
+}
+
+/// Warnings about the response itself rather than the move
+pub fn response_warnings(args: &Args, response: &BattlesnakeMoveResponse) -> Vec<String> {
+    let mut warnings = vec![];
+
+    if let (Some(max), Some(latency)) = (args.max_server_latency, response.latency_ms()) {
+        if latency > max as f64 {
+            warnings.push(format!(
+                "The server reported a latency of {}ms, over the --max-server-latency of {}ms",
+                latency, max
+            ));
+        }
+    }
+
+    warnings
+}
@@ -0,0 +1,389 @@
+//! The HTTP client used to talk to the snake, configured from the command line.
+
+use std::{
+    collections::BTreeMap,
+    fs::read_to_string,
+    path::Path,
+    str::FromStr,
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context, Result};
+use log::{debug, trace};
+use reqwest::{
+    blocking::Client,
+    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE},
+    Method, StatusCode,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::api::ApiVersion;
+use crate::cache::ResponseCache;
+use crate::info;
+use crate::transform::{self, Transform};
+use crate::{Args, BattlesnakeMoveResponse};
+
+pub struct SnakeClient {
+    pub client: Client,
+    transforms: Vec<Transform>,
+    cache: Option<ResponseCache>,
+    /// The field of the response holding the move
+    move_key: String,
+    content_type: HeaderValue,
+    /// Status codes accepted in addition to the 2xx ones
+    accept_status: Vec<u16>,
+    api_version: Option<ApiVersion>,
+    method: RequestMethod,
+    /// The query parameter holding the body with `--method GET`
+    query_key: String,
+    /// How many more times a request is sent after a connection error or a 5xx status
+    retries: u32,
+    /// The wait before the first retry, doubled for each one after it
+    retry_backoff: Duration,
+    /// Print every request and response body to stderr, for `--test-file` and `--stdin`
+    print_exchange: bool,
+}
+
+/// How many times a move request was sent before it succeeded, and how long the last one took
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Attempts {
+    /// 0 for a response from `--cache`
+    pub count: u32,
+    pub last: Duration,
+}
+
+/// A request a test makes before its move request, ie to put the server into a mode with `/config`
+#[derive(Deserialize, Serialize, JsonSchema, Debug, Clone)]
+pub struct SetupCall {
+    /// The HTTP method, `POST` by default
+    #[serde(default = "default_setup_method")]
+    pub method: String,
+    /// Appended to the root of the snake's API, ie `/config`
+    pub path: String,
+    /// Sent as JSON when given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+fn default_setup_method() -> String {
+    "POST".to_owned()
+}
+
+/// How the body is sent, `POST` as the API specifies or `GET` with the body in the query string
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestMethod {
+    Get,
+    Post,
+}
+
+impl FromStr for RequestMethod {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_uppercase().as_str() {
+            "GET" => Ok(RequestMethod::Get),
+            "POST" => Ok(RequestMethod::Post),
+            _ => Err(anyhow!("Unknown method \"{}\", expected GET or POST", s)),
+        }
+    }
+}
+
+impl SnakeClient {
+    pub fn new(args: &Args) -> Result<Self> {
+        if args.method == RequestMethod::Get && args.content_type != "application/json" {
+            return Err(anyhow!(
+                "--content-type can't be used with --method GET, which sends no request body"
+            ));
+        }
+        let file_headers = match &args.headers_file {
+            Some(path) => read_headers_file(path)?,
+            None => vec![],
+        };
+        // `--header` comes last so it overrides the same header from the file
+        let headers = file_headers.iter().chain(&args.header).map(|(n, v)| (n, v));
+        let mut builder = Client::builder().default_headers(header_map(headers)?);
+        if let Some(ms) = args.timeout_ms {
+            builder = builder.timeout(Duration::from_millis(ms));
+        }
+        if let Some(ms) = args.connect_timeout {
+            builder = builder.connect_timeout(Duration::from_millis(ms));
+        }
+        Ok(SnakeClient {
+            client: builder.build()?,
+            transforms: transform::from_args(args),
+            cache: args
+                .cache
+                .clone()
+                .map(|dir| ResponseCache::new(dir, args.refresh, args.no_cache_write)),
+            move_key: args.move_key.clone(),
+            content_type: HeaderValue::from_str(&args.content_type)
+                .map_err(|_| anyhow!("\"{}\" is not a valid content type", args.content_type))?,
+            accept_status: args.accept_status.clone(),
+            api_version: args.api_version,
+            method: args.method,
+            query_key: args.query_key.clone(),
+            retries: args.retries,
+            retry_backoff: Duration::from_millis(args.retry_backoff_ms),
+            print_exchange: args.single_test(),
+        })
+    }
+
+    /// The body that is sent for a state, after applying any transforms
+    pub fn request_body(&self, state: &Value) -> Value {
+        transform::apply(&self.transforms, state)
+    }
+
+    /// Sends a state to the move endpoint, after applying any transforms
+    pub fn request_move(&self, url: &str, state: &Value) -> Result<BattlesnakeMoveResponse> {
+        self.request_move_with_headers(url, state, &HeaderMap::new())
+    }
+
+    /// Like `request_move`, with extra headers that take precedence over `--header`
+    pub fn request_move_with_headers(
+        &self,
+        url: &str,
+        state: &Value,
+        headers: &HeaderMap,
+    ) -> Result<BattlesnakeMoveResponse> {
+        let body = transform::apply(&self.transforms, state);
+
+        if let Some(cached) = self.cache.as_ref().and_then(|c| c.get(url, &body)) {
+            debug!("Using the cached response for {}", url);
+            return self.parse(&cached);
+        }
+
+        let (response, attempts) = self.post_move(url, &body, headers)?;
+        let mut parsed = self.parse(&response)?;
+        parsed.attempts = attempts;
+
+        if let Some(cache) = &self.cache {
+            cache.put(url, &body, &response)?;
+        }
+
+        Ok(parsed)
+    }
+
+    /// Like `request_move_with_headers`, but always asks the server even when `--cache` has a response
+    pub fn request_move_uncached(
+        &self,
+        url: &str,
+        state: &Value,
+        headers: &HeaderMap,
+    ) -> Result<BattlesnakeMoveResponse> {
+        let body = transform::apply(&self.transforms, state);
+        let (response, attempts) = self.post_move(url, &body, headers)?;
+        let mut parsed = self.parse(&response)?;
+        parsed.attempts = attempts;
+        Ok(parsed)
+    }
+
+    /// Parses a move response, checking its shape first when `--api-version` is given
+    fn parse(&self, body: &str) -> Result<BattlesnakeMoveResponse> {
+        if let Some(version) = self.api_version {
+            let raw: Value = serde_json::from_str(body)?;
+            version.validate(&raw, &self.move_key).map_err(|e| {
+                anyhow!("The response doesn't match the API schema, {}: {}", e, body)
+            })?;
+        }
+        BattlesnakeMoveResponse::parse(body, &self.move_key)
+    }
+
+    /// Sends a state to one of the other endpoints, ie `/start`, failing unless it responds with a 200
+    ///
+    /// These are always POSTed like the engine does, whatever `--method` is, with `--content-type`
+    /// and retried like move requests.
+    pub fn post(&self, url: &str, state: &Value) -> Result<()> {
+        let body = transform::apply(&self.transforms, state).to_string();
+        self.retrying(url, || {
+            debug!("POST {}", url);
+            let response = self
+                .client
+                .post(url)
+                .header(CONTENT_TYPE, self.content_type.clone())
+                .body(body.clone())
+                .send()?;
+            // Server errors stay a `reqwest::Error`, so they're retried
+            let response = if response.status().is_server_error() {
+                response.error_for_status()?
+            } else {
+                response
+            };
+            if response.status() != StatusCode::OK {
+                return Err(anyhow!(
+                    "POST {} returned {}, 200 was expected",
+                    url,
+                    response.status()
+                ));
+            }
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    /// Makes a test's setup request, relative to the API root of the move URL, failing unless it succeeds
+    pub fn setup(&self, move_url: &str, setup: &SetupCall) -> Result<()> {
+        let method = Method::from_bytes(setup.method.to_ascii_uppercase().as_bytes())
+            .map_err(|_| anyhow!("\"{}\" is not a valid setup method", setup.method))?;
+        let url = format!("{}{}", info::base_url(move_url), setup.path);
+        debug!("{} {} for setup", method, url);
+        let mut request = self.client.request(method, &url);
+        if let Some(body) = &setup.body {
+            request = request
+                .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+                .body(body.to_string());
+        }
+        request
+            .send()?
+            .error_for_status()
+            .with_context(|| format!("The setup request to {} failed", url))?;
+        Ok(())
+    }
+
+    /// Sends the body, trying again up to `--retries` times when the failure looks transient
+    ///
+    /// Every retry waits twice as long as the one before it, starting at `--retry-backoff-ms`.
+    /// A request that still fails says how many attempts it took.
+    fn post_move(
+        &self,
+        url: &str,
+        body: &Value,
+        headers: &HeaderMap,
+    ) -> Result<(String, Attempts)> {
+        self.retrying(url, || self.send_body(url, body, headers))
+    }
+
+    /// Calls `send` until it succeeds, fails in a way that isn't transient or runs out of retries
+    fn retrying<T>(&self, url: &str, mut send: impl FnMut() -> Result<T>) -> Result<(T, Attempts)> {
+        let mut attempts = Attempts::default();
+        loop {
+            attempts.count += 1;
+            let start = Instant::now();
+            let result = send();
+            attempts.last = start.elapsed();
+            match result {
+                Err(e) if attempts.count <= self.retries && is_transient(&e) => {
+                    let backoff = self
+                        .retry_backoff
+                        .saturating_mul(2u32.saturating_pow(attempts.count - 1));
+                    debug!(
+                        "Attempt {} at {} failed, retrying in {:?}: {:#}",
+                        attempts.count, url, backoff, e
+                    );
+                    thread::sleep(backoff);
+                }
+                Err(e) if attempts.count > 1 => {
+                    return Err(e.context(format!("Gave up after {} attempts", attempts.count)))
+                }
+                result => return result.map(|response| (response, attempts)),
+            }
+        }
+    }
+
+    fn send_body(&self, url: &str, body: &Value, headers: &HeaderMap) -> Result<String> {
+        trace!("Request body: {}", body);
+        let request = match self.method {
+            RequestMethod::Post => {
+                debug!("POST {}", url);
+                self.client
+                    .post(url)
+                    .header(CONTENT_TYPE, self.content_type.clone())
+                    .body(body.to_string())
+            }
+            RequestMethod::Get => {
+                debug!("GET {}", url);
+                self.client
+                    .get(url)
+                    .query(&[(&self.query_key, body.to_string())])
+            }
+        };
+        if self.print_exchange {
+            eprintln!("{} {}\n{}\n", method_name(self.method), url, pretty(body));
+        }
+        let response = request.headers(headers.clone()).send()?;
+        let status = response.status();
+        let response = if self.accept_status.contains(&response.status().as_u16()) {
+            response
+        } else {
+            response.error_for_status()?
+        };
+        let response = response.text()?;
+        trace!("Response body: {}", response);
+        if self.print_exchange {
+            let parsed = serde_json::from_str(&response).map(|r: Value| pretty(&r));
+            eprintln!(
+                "{}\n{}\n",
+                status,
+                parsed.unwrap_or_else(|_| response.clone())
+            );
+        }
+        Ok(response)
+    }
+}
+
+fn method_name(method: RequestMethod) -> &'static str {
+    match method {
+        RequestMethod::Get => "GET",
+        RequestMethod::Post => "POST",
+    }
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+/// Connection errors and 5xx statuses, which are worth retrying as the next attempt may succeed
+fn is_transient(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<reqwest::Error>()
+        .is_some_and(|e| e.is_connect() || e.status().is_some_and(|s| s.is_server_error()))
+}
+
+/// Builds a header map, failing on names or values that aren't valid in a header
+pub fn header_map<'a>(
+    headers: impl IntoIterator<Item = (&'a String, &'a String)>,
+) -> Result<HeaderMap> {
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        map.insert(
+            HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| anyhow!("\"{}\" is not a valid header name", name))?,
+            HeaderValue::from_str(value)
+                .map_err(|_| anyhow!("The value of the \"{}\" header is not valid", name))?,
+        );
+    }
+    Ok(map)
+}
+
+/// Parses a `Name: Value` command line argument
+pub fn parse_header(s: &str) -> Result<(String, String)> {
+    let (name, value) = s
+        .split_once(':')
+        .ok_or_else(|| anyhow!("Expected \"Name: Value\" but got \"{}\"", s))?;
+    Ok((name.trim().to_owned(), value.trim().to_owned()))
+}
+
+/// Reads the headers from a `--headers-file`
+///
+/// The file is either a JSON object of names to values, or one `Name: Value`
+/// per line, where blank lines and lines starting with `#` are ignored.
+pub fn read_headers_file(path: &Path) -> Result<Vec<(String, String)>> {
+    let contents = read_to_string(path)
+        .with_context(|| format!("Could not read the headers file {}", path.display()))?;
+    if contents.trim_start().starts_with('{') {
+        let headers: BTreeMap<String, String> = serde_json::from_str(&contents)
+            .with_context(|| format!("{} is not a JSON object of strings", path.display()))?;
+        return Ok(headers.into_iter().collect());
+    }
+
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(i, line)| {
+            parse_header(line).with_context(|| format!("{} line {}", path.display(), i + 1))
+        })
+        .collect()
+}
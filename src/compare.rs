@@ -0,0 +1,228 @@
+//! Running the suite against several servers at once, used when `--url` is given more than once.
+//!
+//! Rather than the usual failure report this prints a matrix of the move every
+//! server chose for every test, so candidate strategies can be compared. Each
+//! test is sent to the servers concurrently, up to `--jobs` at a time.
+
+use std::time::{Duration, Instant};
+
+use colored::*;
+
+use crate::client::SnakeClient;
+use crate::parallel;
+use crate::{Args, TestCase};
+
+struct ServerStats {
+    passed: usize,
+    latencies: Vec<Duration>,
+}
+
+/// Returns whether every server passed every test
+pub fn run_matrix(
+    args: &Args,
+    client: &SnakeClient,
+    urls: &[String],
+    test_cases: &[TestCase],
+) -> bool {
+    let mut stats: Vec<ServerStats> = urls
+        .iter()
+        .map(|_| ServerStats {
+            passed: 0,
+            latencies: vec![],
+        })
+        .collect();
+
+    let path_width = test_cases
+        .iter()
+        .map(|t| t.path.to_str().unwrap().len())
+        .max()
+        .unwrap_or(0)
+        .max("Test".len());
+    const CELL_WIDTH: usize = 8;
+
+    for (i, url) in urls.iter().enumerate() {
+        println!("#{}: {}", i + 1, url);
+    }
+    println!();
+
+    print!("{:width$}", "Test", width = path_width);
+    for i in 0..urls.len() {
+        print!("  {:>width$}", format!("#{}", i + 1), width = CELL_WIDTH);
+    }
+    println!();
+
+    for test_case in test_cases {
+        let responses = parallel::map_bounded(urls, args.jobs.unwrap_or(4), |url| {
+            let start = Instant::now();
+            let response = client.request_move(url, &test_case.state);
+            (response.ok().map(|r| r.r#move), start.elapsed())
+        });
+
+        let mut moves: Vec<Option<String>> = vec![];
+        for ((chosen, latency), stats) in responses.into_iter().zip(stats.iter_mut()) {
+            stats.latencies.push(latency);
+            if chosen.as_ref().is_some_and(|m| test_case.accepts(m)) {
+                stats.passed += 1;
+            }
+            moves.push(chosen);
+        }
+
+        print!(
+            "{:width$}",
+            test_case.path.to_str().unwrap(),
+            width = path_width
+        );
+        for chosen in &moves {
+            let cell = match chosen {
+                Some(m) if test_case.accepts(m) => {
+                    format!("{:>width$}", m, width = CELL_WIDTH).color(args.expected_color)
+                }
+                Some(m) => format!("{:>width$}", m, width = CELL_WIDTH).color(args.actual_color),
+                None => {
+                    format!("{:>width$}", "error", width = CELL_WIDTH).color(args.failure_color)
+                }
+            };
+            print!("  {}", cell);
+        }
+        let agreed = moves.windows(2).all(|w| w[0] == w[1]);
+        if !agreed {
+            print!("  {}", "disagree".color(args.failure_color));
+        }
+        println!();
+    }
+
+    println!();
+    for (i, s) in stats.iter().enumerate() {
+        let total: Duration = s.latencies.iter().sum();
+        let mean = total / s.latencies.len().max(1) as u32;
+        let max = s.latencies.iter().max().copied().unwrap_or_default();
+        println!(
+            "#{}: {} out of {} tests passed, mean latency {}, max latency {}",
+            i + 1,
+            s.passed,
+            test_cases.len(),
+            args.latency(mean),
+            args.latency(max)
+        );
+    }
+
+    stats.iter().all(|s| s.passed == test_cases.len())
+}
+
+/// `--compare-url`, listing only the tests where the two snakes chose different moves
+///
+/// Returns false when the snake under `--url` failed a test the other one passed, so a
+/// refactor that makes things worse fails the run.
+pub fn run_comparison(
+    args: &Args,
+    client: &SnakeClient,
+    url: &str,
+    compare_url: &str,
+    test_cases: &[TestCase],
+) -> bool {
+    println!("A: {}\nB: {}\n", url, compare_url);
+
+    let urls = [url.to_owned(), compare_url.to_owned()];
+    let mut disagreements = vec![];
+    let mut passed = [0, 0];
+    for test_case in test_cases {
+        let moves = parallel::map_bounded(&urls, 2, |url| {
+            client
+                .request_move(url, &test_case.state)
+                .ok()
+                .map(|r| r.r#move)
+        });
+        let accepted: Vec<_> = moves
+            .iter()
+            .map(|m| m.as_ref().is_some_and(|m| test_case.accepts(m)))
+            .collect();
+        for (passed, accepted) in passed.iter_mut().zip(&accepted) {
+            *passed += *accepted as usize;
+        }
+        if moves[0] != moves[1] {
+            disagreements.push((test_case, moves, accepted));
+        }
+    }
+
+    if disagreements.is_empty() {
+        println!(
+            "Both snakes chose the same move in all {} tests",
+            test_cases.len()
+        );
+    } else {
+        let path_width = disagreements
+            .iter()
+            .map(|(t, _, _)| t.path.to_str().unwrap().len())
+            .max()
+            .unwrap_or(0)
+            .max("Test".len());
+        const CELL_WIDTH: usize = 8;
+        println!(
+            "{:path_width$}  {:>cell$}  {:>cell$}  {:<16}  Matched",
+            "Test",
+            "A",
+            "B",
+            "Expected",
+            path_width = path_width,
+            cell = CELL_WIDTH
+        );
+        let mut regressions = 0;
+        for (test_case, moves, accepted) in &disagreements {
+            let cells: Vec<_> = moves
+                .iter()
+                .zip(accepted)
+                .map(|(m, accepted)| {
+                    let cell = format!(
+                        "{:>width$}",
+                        m.as_deref().unwrap_or("error"),
+                        width = CELL_WIDTH
+                    );
+                    if *accepted {
+                        cell.color(args.expected_color)
+                    } else {
+                        cell.color(args.actual_color)
+                    }
+                })
+                .collect();
+            let matched = match (accepted[0], accepted[1]) {
+                (true, true) => "both",
+                (true, false) => "A",
+                (false, true) => {
+                    regressions += 1;
+                    "B"
+                }
+                (false, false) => "neither",
+            };
+            let expected = if test_case.expected.is_empty() {
+                "any".to_owned()
+            } else {
+                format!("[{}]", test_case.expected.join(", "))
+            };
+            println!(
+                "{:path_width$}  {}  {}  {:<16}  {}",
+                test_case.path.to_str().unwrap(),
+                cells[0],
+                cells[1],
+                expected,
+                matched,
+                path_width = path_width
+            );
+        }
+        println!(
+            "\nThe snakes disagreed on {} of {} tests, B was right and A wasn't on {}",
+            disagreements.len(),
+            test_cases.len(),
+            regressions
+        );
+    }
+    println!(
+        "A passed {} and B passed {} out of {} tests",
+        passed[0],
+        passed[1],
+        test_cases.len()
+    );
+
+    disagreements
+        .iter()
+        .all(|(_, _, accepted)| accepted[0] || !accepted[1])
+}
@@ -0,0 +1,118 @@
+//! `battlesnake_tests.toml`, a file of defaults for the command line options so the same
+//! `--url`, colors and directory don't have to be passed every time.
+
+use std::{ffi::OsString, fs::read_to_string, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+use structopt::StructOpt;
+use toml_edit::{Document, Item, Value};
+
+use crate::Args;
+
+/// The config file that is read from the working directory when `--config` isn't given
+pub const DEFAULT_PATH: &str = "battlesnake_tests.toml";
+
+/// The command line with the options from the config file inserted before the ones given on it
+///
+/// An option from the file is left out when the command line has it, so the command line always
+/// takes precedence, including for options that can be repeated. When the command line can't be
+/// parsed it is returned unchanged, for structopt to report the error or print the help.
+pub fn with_config_file(argv: Vec<OsString>) -> Result<Vec<OsString>> {
+    let matches = match Args::clap().get_matches_from_safe(&argv) {
+        Ok(matches) => matches,
+        Err(_) => return Ok(argv),
+    };
+    let path = match matches.value_of_os("config") {
+        Some(path) => Path::new(path).to_path_buf(),
+        None if Path::new(DEFAULT_PATH).is_file() => Path::new(DEFAULT_PATH).to_path_buf(),
+        None => return Ok(argv),
+    };
+
+    let document: Document = read_to_string(&path)
+        .with_context(|| format!("Could not read the config file {}", path.display()))?
+        .parse()
+        .with_context(|| format!("Could not parse the config file {}", path.display()))?;
+
+    let mut options = vec![];
+    for (key, item) in document.iter() {
+        let name = key.replace('_', "-");
+        if matches.occurrences_of(arg_name(&name)) > 0 {
+            continue;
+        }
+        options.extend(
+            option(&name, item)
+                .with_context(|| format!("In the config file {}", path.display()))?,
+        );
+    }
+
+    // Checked on their own, so a mistake in the file is reported as one
+    let program = argv.first().cloned().unwrap_or_default();
+    if let Err(e) =
+        Args::clap().get_matches_from_safe(std::iter::once(program.clone()).chain(options.clone()))
+    {
+        return Err(anyhow!(
+            "The config file {} has an invalid option: {}",
+            path.display(),
+            e.message
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim_start_matches("error: ")
+        ));
+    }
+
+    Ok(std::iter::once(program)
+        .chain(options)
+        .chain(argv.into_iter().skip(1))
+        .collect())
+}
+
+/// The name structopt gives the option, which is its long flag apart from the few with their
+/// own name
+fn arg_name(long: &str) -> &str {
+    match long {
+        "url" => "Battlesnake URL to test against",
+        "dir" | "test-directory" => "Directory containing test cases",
+        long => long,
+    }
+}
+
+/// The command line arguments for a key of the file: a flag for `true`, nothing for `false`,
+/// and the flag repeated for every value of an array
+fn option(name: &str, item: &Item) -> Result<Vec<OsString>> {
+    let flag = OsString::from(format!(
+        "--{}",
+        if name == "test-directory" {
+            "dir"
+        } else {
+            name
+        }
+    ));
+    let value = item
+        .as_value()
+        .ok_or_else(|| anyhow!("\"{}\" is a table, only options can be set", name))?;
+
+    match value {
+        Value::Boolean(b) if *b.value() => Ok(vec![flag]),
+        Value::Boolean(_) => Ok(vec![]),
+        Value::Array(values) => values
+            .iter()
+            .map(|v| Ok(vec![flag.clone(), scalar(name, v)?]))
+            .collect::<Result<Vec<_>>>()
+            .map(|pairs| pairs.concat()),
+        value => Ok(vec![flag, scalar(name, value)?]),
+    }
+}
+
+fn scalar(name: &str, value: &Value) -> Result<OsString> {
+    match value {
+        Value::String(s) => Ok(s.value().into()),
+        Value::Integer(i) => Ok(i.value().to_string().into()),
+        Value::Float(f) => Ok(f.value().to_string().into()),
+        value => Err(anyhow!(
+            "\"{}\" is {}, expected a string, number, boolean or array of them",
+            name,
+            value.type_name()
+        )),
+    }
+}
@@ -0,0 +1,52 @@
+//! The `--confusion-matrix` report, which tabulates the chosen move against the
+//! expected one to show systematic biases.
+
+use crate::board::Direction;
+use crate::TestRun;
+
+/// Prints how often each direction was chosen when each direction was expected
+///
+/// Only tests with exactly one expected move that got a move back are counted,
+/// a move that isn't a direction is counted under `other`.
+pub fn print_confusion_matrix(results: &[TestRun]) {
+    let mut counts = [[0usize; 5]; 4];
+    let mut counted = 0;
+
+    for r in results {
+        let expected = match r.test_case.expected.as_slice() {
+            [expected] => expected.parse::<Direction>().ok(),
+            _ => None,
+        };
+        let (expected, actual) = match expected.zip(r.actual_move.as_deref()) {
+            Some(pair) => pair,
+            None => continue,
+        };
+        let column = actual
+            .parse::<Direction>()
+            .map(index)
+            .unwrap_or(Direction::ALL.len());
+        counts[index(expected)][column] += 1;
+        counted += 1;
+    }
+
+    println!(
+        "Expected vs chosen move, over {} tests with a single expected move:",
+        counted
+    );
+    print!("  {:>8}", "");
+    for d in Direction::ALL {
+        print!(" {:>6}", d.as_str());
+    }
+    println!(" {:>6}", "other");
+    for (expected, row) in Direction::ALL.iter().zip(&counts) {
+        print!("  {:>8}", expected.as_str());
+        for count in row {
+            print!(" {:>6}", count);
+        }
+        println!();
+    }
+}
+
+fn index(direction: Direction) -> usize {
+    Direction::ALL.iter().position(|d| *d == direction).unwrap()
+}
@@ -0,0 +1,57 @@
+//! The `convert` subcommand, which rewrites test files between JSON and YAML.
+
+use std::{
+    fs::{create_dir_all, read_to_string, write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Result};
+use glob::glob;
+
+use crate::format::FileFormat;
+
+/// Converts a single test file, or every test file below a directory
+///
+/// When converting a directory the input tree is mirrored into `output`.
+pub fn convert(input: &Path, output: Option<&Path>) -> Result<()> {
+    if !input.is_dir() {
+        let format = FileFormat::from_path(input)?;
+        let output = output
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| input.with_extension(format.other().extension()));
+        return convert_file(input, &output);
+    }
+
+    let output = output
+        .ok_or_else(|| anyhow!("An output directory is required when converting a directory"))?;
+
+    for pattern in &["json", "yaml", "yml"] {
+        for entry in glob(&format!("{}/**/*.{}", input.display(), pattern))? {
+            let path = entry?;
+            let format = FileFormat::from_path(&path)?;
+            let relative = path.strip_prefix(input)?;
+            let destination: PathBuf = output
+                .join(relative)
+                .with_extension(format.other().extension());
+            convert_file(&path, &destination)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn convert_file(input: &Path, output: &Path) -> Result<()> {
+    let from = FileFormat::from_path(input)?;
+    let to = FileFormat::from_path(output)?;
+
+    let test_case_file = from.parse(&read_to_string(input)?)?;
+
+    if let Some(parent) = output.parent() {
+        create_dir_all(parent)?;
+    }
+    write(output, to.serialize(&test_case_file)?)?;
+
+    println!("Converted {} to {}", input.display(), output.display());
+
+    Ok(())
+}
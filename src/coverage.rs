@@ -0,0 +1,66 @@
+//! The `--coverage` report, which tabulates how varied the test states are.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::board::{Direction, GameState};
+use crate::TestCase;
+
+pub fn print_coverage(test_cases: &[TestCase]) {
+    let mut board_sizes: BTreeMap<(i32, i32), usize> = BTreeMap::new();
+    let mut opponent_counts: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut opponent_names = BTreeSet::new();
+    let mut threatened = 0;
+    let mut unparsed = 0;
+
+    for test_case in test_cases {
+        let state = match GameState::from_value(&test_case.state) {
+            Ok(state) => state,
+            Err(_) => {
+                unparsed += 1;
+                continue;
+            }
+        };
+        let opponents = state
+            .board
+            .snakes
+            .iter()
+            .filter(|s| s.id != state.you.id)
+            .count();
+        opponent_names.extend(
+            state
+                .board
+                .snakes
+                .iter()
+                .filter(|s| s.id != state.you.id)
+                .map(|s| s.name.clone()),
+        );
+        if Direction::ALL
+            .iter()
+            .any(|d| !state.losing_head_to_heads(*d).is_empty())
+        {
+            threatened += 1;
+        }
+
+        *board_sizes
+            .entry((state.board.width, state.board.height))
+            .or_default() += 1;
+        *opponent_counts.entry(opponents).or_default() += 1;
+    }
+
+    println!("Board sizes:");
+    for ((width, height), count) in &board_sizes {
+        println!("  {:>7} {} tests", format!("{}x{}", width, height), count);
+    }
+    println!("Opponents:");
+    for (opponents, count) in &opponent_counts {
+        println!("  {:>7} {} tests", opponents, count);
+    }
+    println!("{} distinct opponents, by name", opponent_names.len());
+    println!(
+        "{} tests have an equal or longer opponent that could win a head-to-head next turn",
+        threatened
+    );
+    if unparsed > 0 {
+        println!("{} tests could not be parsed and aren't counted", unparsed);
+    }
+}
@@ -0,0 +1,210 @@
+//! `diagram`, a test's board drawn as a grid of characters instead of written out as a state,
+//! expanded into the state the engine would send when the test is loaded.
+
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::board::{Coord, Direction};
+use crate::TestCaseFile;
+
+/// The letter of the snake the test is for, sent as `you`
+const YOU: char = 'Y';
+
+/// A board drawn a row per line, the top line being the highest `y`
+///
+/// `.` is an empty cell, `f` food and `#` a hazard. A snake is an uppercase letter for its head
+/// followed by the same letter in lowercase for the rest of its body, `Y` being the snake the
+/// test is for. Spaces between the cells are ignored.
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug)]
+pub struct Diagram {
+    pub board: String,
+    /// The health of each snake by its letter, 100 for the ones that aren't listed
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub health: BTreeMap<char, i32>,
+    #[serde(default)]
+    pub turn: u32,
+    /// The test's `ruleset` when it's left out, or `standard` if neither is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ruleset: Option<String>,
+}
+
+/// Replaces the file's `diagram` with the `state` it draws
+pub fn expand(test_case_file: &mut TestCaseFile) -> Result<()> {
+    let diagram = match &test_case_file.diagram {
+        Some(diagram) => diagram,
+        None => return Ok(()),
+    };
+    if !test_case_file.state.is_null() || !test_case_file.steps.is_empty() {
+        return Err(anyhow!(
+            "Has a diagram and a state or steps, only one of them can be used"
+        ));
+    }
+    test_case_file.state = diagram
+        .to_state(test_case_file.ruleset.as_deref())
+        .map_err(|e| anyhow!("The diagram is invalid, {}", e))?;
+    Ok(())
+}
+
+impl Diagram {
+    /// The state the diagram draws, with the diagram's own ruleset or else `default_ruleset`
+    pub fn to_state(&self, default_ruleset: Option<&str>) -> Result<Value> {
+        let rows: Vec<Vec<char>> = self
+            .board
+            .lines()
+            .map(|line| {
+                line.chars()
+                    .filter(|c| !c.is_whitespace())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|row| !row.is_empty())
+            .collect();
+        let width = rows.first().map_or(0, Vec::len);
+        if width == 0 {
+            return Err(anyhow!("the board has no cells"));
+        }
+        if let Some(i) = rows.iter().position(|row| row.len() != width) {
+            return Err(anyhow!(
+                "row {} has {} cells but the first one has {}",
+                i + 1,
+                rows[i].len(),
+                width
+            ));
+        }
+        let height = rows.len() as i32;
+
+        let mut food = vec![];
+        let mut hazards = vec![];
+        let mut heads = BTreeMap::new();
+        let mut bodies: HashSet<(char, Coord)> = HashSet::new();
+        for (i, row) in rows.iter().enumerate() {
+            for (x, c) in row.iter().copied().enumerate() {
+                let cell = Coord {
+                    x: x as i32,
+                    y: height - 1 - i as i32,
+                };
+                match c {
+                    '.' => {}
+                    'f' => food.push(cell),
+                    '#' => hazards.push(cell),
+                    'F' => return Err(anyhow!("F can't be a snake, f is food")),
+                    c if c.is_ascii_uppercase() => {
+                        if heads.insert(c, cell).is_some() {
+                            return Err(anyhow!("snake {} has more than one head", c));
+                        }
+                    }
+                    c if c.is_ascii_lowercase() => {
+                        bodies.insert((c.to_ascii_uppercase(), cell));
+                    }
+                    c => return Err(anyhow!("'{}' at {} isn't a cell", c, cell)),
+                }
+            }
+        }
+        if !heads.contains_key(&YOU) {
+            return Err(anyhow!("there is no {} for the snake the test is for", YOU));
+        }
+        if let Some((letter, cell)) = bodies.iter().find(|(l, _)| !heads.contains_key(l)) {
+            return Err(anyhow!(
+                "the body of snake {} at {} has no head",
+                letter,
+                cell
+            ));
+        }
+        if let Some(letter) = self.health.keys().find(|l| !heads.contains_key(l)) {
+            return Err(anyhow!(
+                "health is given for snake {}, which isn't on the board",
+                letter
+            ));
+        }
+
+        // `you` first, as the engine lists it, then the others by their letter
+        let mut letters: Vec<char> = heads.keys().copied().collect();
+        letters.sort_by_key(|l| (*l != YOU, *l));
+        let snakes = letters
+            .into_iter()
+            .map(|letter| {
+                let body = trace_body(letter, heads[&letter], &mut bodies)?;
+                let health = self.health.get(&letter).copied().unwrap_or(100);
+                Ok(snake(letter, &body, health))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if let Some((letter, cell)) = bodies.iter().next() {
+            return Err(anyhow!(
+                "the body of snake {} at {} isn't connected to its head",
+                letter,
+                cell
+            ));
+        }
+
+        Ok(json!({
+            "game": {
+                "id": "diagram",
+                "ruleset": { "name": self.ruleset.as_deref().or(default_ruleset).unwrap_or("standard"), "version": "" },
+                "timeout": 500,
+            },
+            "turn": self.turn,
+            "board": {
+                "width": width,
+                "height": height,
+                "food": food.iter().map(cell).collect::<Vec<_>>(),
+                "hazards": hazards.iter().map(cell).collect::<Vec<_>>(),
+                "snakes": snakes,
+            },
+            "you": snakes[0],
+        }))
+    }
+}
+
+/// The body from the head to the tail, following the cells of the snake's letter
+///
+/// Every cell may only have one next cell that isn't part of the body yet, otherwise the order
+/// can't be told from the drawing. The cells that were followed are removed from `bodies`.
+fn trace_body(
+    letter: char,
+    head: Coord,
+    bodies: &mut HashSet<(char, Coord)>,
+) -> Result<Vec<Coord>> {
+    let mut body = vec![head];
+    loop {
+        let last = body[body.len() - 1];
+        let next: Vec<_> = Direction::ALL
+            .iter()
+            .map(|d| last.moved(*d))
+            .filter(|c| bodies.contains(&(letter, *c)))
+            .collect();
+        match next.as_slice() {
+            [] => return Ok(body),
+            [c] => {
+                bodies.remove(&(letter, *c));
+                body.push(*c);
+            }
+            _ => {
+                return Err(anyhow!(
+                    "the body of snake {} branches at {}, so its order isn't clear",
+                    letter,
+                    last
+                ))
+            }
+        }
+    }
+}
+
+fn cell(c: &Coord) -> Value {
+    json!({ "x": c.x, "y": c.y })
+}
+
+fn snake(letter: char, body: &[Coord], health: i32) -> Value {
+    json!({
+        "id": letter.to_string(),
+        "name": letter.to_string(),
+        "health": health,
+        "body": body.iter().map(cell).collect::<Vec<_>>(),
+        "head": cell(&body[0]),
+        "length": body.len(),
+        "latency": "0",
+        "shout": "",
+    })
+}
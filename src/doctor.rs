@@ -0,0 +1,102 @@
+//! The `doctor` subcommand, which checks the setup a run depends on without running any tests.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+
+use crate::api::ApiVersion;
+use crate::client::SnakeClient;
+use crate::info::{self, base_url};
+use crate::{required_url, test_file_paths, Args};
+
+/// Prints a line per check, returning whether all of them passed
+///
+/// A check that depends on an earlier one, like the apiversion on the server
+/// being reachable, isn't run when that one failed.
+pub fn doctor(args: &Args) -> Result<bool> {
+    let mut all_passed = true;
+    let mut report = |check: &str, result: Result<String>| match result {
+        Ok(detail) => println!("[ok]     {}: {}", check, detail),
+        Err(e) => {
+            all_passed = false;
+            println!("{} {}: {}", "[failed]".color(args.failure_color), check, e);
+        }
+    };
+
+    report("Test files", check_test_files(args));
+
+    let client = SnakeClient::new(args);
+    let client_check = client
+        .as_ref()
+        .map(|_| "every header and the content type are valid".to_owned())
+        .map_err(|e| anyhow!("{}", e));
+    report("Headers", client_check);
+
+    if let (Ok(client), Ok(url)) = (&client, required_url(args)) {
+        let reachable = check_reachable(client, url);
+        let is_reachable = reachable.is_ok();
+        report("Connectivity", reachable);
+        if is_reachable {
+            report("API version", check_apiversion(args, client, url));
+        }
+    } else if let Err(e) = required_url(args) {
+        report("Connectivity", Err(e));
+    }
+
+    Ok(all_passed)
+}
+
+fn check_test_files(args: &Args) -> Result<String> {
+    if args.files.is_empty() && !Path::new(&args.test_directory).is_dir() {
+        return Err(anyhow!(
+            "the test directory {} does not exist",
+            args.test_directory
+        ));
+    }
+    let paths = test_file_paths(args)?;
+    if paths.is_empty() {
+        let patterns: Vec<_> = args
+            .extensions()
+            .iter()
+            .map(|e| format!("**/*.{}", e))
+            .collect();
+        return Err(anyhow!(
+            "no files in {} match {}",
+            args.test_directory,
+            patterns.join(", ")
+        ));
+    }
+    Ok(format!("{} found", paths.len()))
+}
+
+fn check_reachable(client: &SnakeClient, url: &str) -> Result<String> {
+    let root = format!("{}/", base_url(url));
+    let response = client.client.get(&root).send()?;
+    if !response.status().is_success() {
+        return Err(anyhow!("GET {} returned {}", root, response.status()));
+    }
+    Ok(format!("GET {} returned {}", root, response.status()))
+}
+
+/// The reported apiversion has to be one this tool knows, and match `--api-version` or `--expect-apiversion`
+fn check_apiversion(args: &Args, client: &SnakeClient, url: &str) -> Result<String> {
+    let info = info::fetch_info(client, url)?;
+    let reported = info
+        .apiversion
+        .as_deref()
+        .ok_or_else(|| anyhow!("the server did not report an apiversion"))?;
+    let version: ApiVersion = reported.parse()?;
+    let expected = args
+        .expect_apiversion
+        .as_deref()
+        .or_else(|| args.api_version.map(ApiVersion::as_str));
+    match expected {
+        Some(expected) if expected != version.as_str() => Err(anyhow!(
+            "the server reports \"{}\" but \"{}\" was expected",
+            reported,
+            expected
+        )),
+        _ => Ok(format!("the server reports \"{}\"", reported)),
+    }
+}
@@ -0,0 +1,206 @@
+//! The game export format used by the Battlesnake engine, and conversion into the
+//! game state the `/move` endpoint receives.
+
+use anyhow::{Context, Result};
+use log::debug;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::board::{Board, Coord, Game, GameState, Ruleset, Snake};
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct EngineExport {
+    pub game: EngineGame,
+    pub frames: Vec<EngineFrame>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct EngineGame {
+    #[serde(rename = "ID")]
+    pub id: String,
+    pub width: i32,
+    pub height: i32,
+    #[serde(default)]
+    pub ruleset: Map<String, Value>,
+    #[serde(default)]
+    pub snake_timeout: u32,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct EngineFrame {
+    pub turn: u32,
+    pub snakes: Vec<EngineSnake>,
+    #[serde(default)]
+    pub food: Vec<EngineCoord>,
+    #[serde(default)]
+    pub hazards: Vec<EngineCoord>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+pub struct EngineSnake {
+    #[serde(rename = "ID")]
+    pub id: String,
+    pub name: String,
+    pub body: Vec<EngineCoord>,
+    pub health: i32,
+    #[serde(default)]
+    pub death: Option<Value>,
+    #[serde(default)]
+    pub shout: String,
+    #[serde(default)]
+    pub squad: String,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct EngineCoord {
+    #[serde(rename = "X")]
+    pub x: i32,
+    #[serde(rename = "Y")]
+    pub y: i32,
+}
+
+impl From<EngineCoord> for Coord {
+    fn from(c: EngineCoord) -> Self {
+        Coord { x: c.x, y: c.y }
+    }
+}
+
+impl EngineExport {
+    /// Finds a snake by id or by name
+    pub fn find_snake_id(&self, id_or_name: &str) -> Option<String> {
+        self.frames
+            .iter()
+            .flat_map(|f| f.snakes.iter())
+            .find(|s| s.id == id_or_name || s.name == id_or_name)
+            .map(|s| s.id.clone())
+    }
+}
+
+impl EngineSnake {
+    fn to_snake(&self) -> Snake {
+        let body: Vec<Coord> = self.body.iter().copied().map(Coord::from).collect();
+        let mut extra = Map::new();
+        extra.insert("shout".to_owned(), Value::String(self.shout.clone()));
+        extra.insert("squad".to_owned(), Value::String(self.squad.clone()));
+
+        Snake {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            health: self.health,
+            head: body[0],
+            length: body.len(),
+            body,
+            extra,
+        }
+    }
+}
+
+impl EngineFrame {
+    pub fn snake(&self, id: &str) -> Option<&EngineSnake> {
+        self.snakes.iter().find(|s| s.id == id)
+    }
+
+    /// The state the engine would have sent to the snake with the given id on this turn
+    ///
+    /// Returns `None` when that snake was no longer alive.
+    pub fn to_state(&self, game: &EngineGame, you_id: &str) -> Option<GameState> {
+        let alive: Vec<Snake> = self
+            .snakes
+            .iter()
+            .filter(|s| s.death.is_none() && !s.body.is_empty())
+            .map(EngineSnake::to_snake)
+            .collect();
+        let you = alive.iter().find(|s| s.id == you_id)?.clone();
+
+        let mut ruleset = game.ruleset.clone();
+        let name = ruleset
+            .remove("name")
+            .and_then(|n| n.as_str().map(str::to_owned))
+            .unwrap_or_else(|| "standard".to_owned());
+
+        Some(GameState {
+            game: Game {
+                id: game.id.clone(),
+                ruleset: Ruleset {
+                    name,
+                    extra: ruleset,
+                },
+                timeout: game.snake_timeout,
+                extra: Map::new(),
+            },
+            turn: self.turn,
+            board: Board {
+                width: game.width,
+                height: game.height,
+                food: self.food.iter().copied().map(Coord::from).collect(),
+                hazards: self.hazards.iter().copied().map(Coord::from).collect(),
+                snakes: alive,
+                extra: Map::new(),
+            },
+            you,
+            extra: Map::new(),
+        })
+    }
+}
+
+/// The engine's response for a single game, which is followed by its frames
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GameResponse {
+    game: EngineGame,
+}
+
+/// A page of a game's frames, oldest first
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct FramesResponse {
+    #[serde(default)]
+    frames: Vec<EngineFrame>,
+}
+
+/// How many frames are fetched per request, the most the engine returns at once
+const FRAMES_PER_PAGE: usize = 100;
+
+/// Downloads a finished game and every one of its frames from the engine API
+pub fn fetch_export(engine_url: &str, game_id: &str) -> Result<EngineExport> {
+    let client = reqwest::blocking::Client::new();
+    let game_url = format!("{}/games/{}", engine_url.trim_end_matches('/'), game_id);
+    debug!("GET {}", game_url);
+    let game: GameResponse = client
+        .get(&game_url)
+        .send()?
+        .error_for_status()
+        .with_context(|| format!("Could not fetch the game {}", game_id))?
+        .json()?;
+
+    let mut frames = vec![];
+    loop {
+        let url = format!(
+            "{}/frames?offset={}&limit={}",
+            game_url,
+            frames.len(),
+            FRAMES_PER_PAGE
+        );
+        debug!("GET {}", url);
+        let page: FramesResponse = client
+            .get(&url)
+            .send()?
+            .error_for_status()
+            .with_context(|| format!("Could not fetch the frames of the game {}", game_id))?
+            .json()?;
+        let done = page.frames.len() < FRAMES_PER_PAGE;
+        frames.extend(page.frames);
+        if done {
+            break;
+        }
+    }
+
+    Ok(EngineExport {
+        game: game.game,
+        frames,
+    })
+}
@@ -0,0 +1,51 @@
+//! Expansion of `${VAR}` references in command line arguments, so CI configs can
+//! pass `--url http://${SNAKE_HOST}:${SNAKE_PORT}/move` without a shell.
+
+use std::env;
+
+use anyhow::{anyhow, Result};
+
+use crate::Args;
+
+/// Expands the `--url`, `--compare-url`, `--oracle-url` and `--header` values against the process
+/// environment
+pub fn expand_args(args: &mut Args) -> Result<()> {
+    for url in args
+        .url
+        .iter_mut()
+        .chain(&mut args.compare_url)
+        .chain(&mut args.oracle_url)
+    {
+        *url = expand(url)?;
+    }
+    for (_, value) in &mut args.header {
+        *value = expand(value)?;
+    }
+    Ok(())
+}
+
+/// Replaces every `${VAR}` with the value of `VAR`, failing if it isn't set
+pub fn expand(s: &str) -> Result<String> {
+    let mut expanded = String::new();
+    let mut rest = s;
+
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| anyhow!("Unterminated \"${{\" in \"{}\"", s))?;
+        let name = &rest[start + 2..start + end];
+        let value = env::var(name).map_err(|_| {
+            anyhow!(
+                "The environment variable \"{}\" referenced in \"{}\" is not set",
+                name,
+                s
+            )
+        })?;
+        expanded.push_str(&value);
+        rest = &rest[start + end + 1..];
+    }
+    expanded.push_str(rest);
+
+    Ok(expanded)
+}
@@ -0,0 +1,105 @@
+//! The `--explain` view of a failure: the board, the moves as arrows, and what the board
+//! module can tell about why the snake's move was worse than the expected one.
+
+use crate::board::{Direction, GameState};
+use crate::lint::highlight;
+use crate::{Args, TestCase};
+
+/// The explanation printed below a failure, `None` when the state or the move can't be parsed
+pub fn explain(args: &Args, test_case: &TestCase, actual: &str) -> Option<String> {
+    let state = GameState::from_value(&test_case.state).ok()?;
+    let actual_direction: Direction = actual.parse().ok()?;
+    let expected: Vec<Direction> = test_case
+        .expected
+        .iter()
+        .filter_map(|m| m.parse().ok())
+        .collect();
+
+    let mut out = String::new();
+    let arrows = |directions: &[Direction]| {
+        let arrows: Vec<_> = directions
+            .iter()
+            .map(|d| format!("{} {}", arrow(*d), d))
+            .collect();
+        arrows.join(", ")
+    };
+    if !expected.is_empty() {
+        out += &format!("Expected: {}\n", arrows(&expected));
+    }
+    out += &format!("Actual:   {}\n", arrows(&[actual_direction]));
+
+    let target = state.geometry().step(state.you.head, actual_direction);
+    let board = state.render_with_target(target, |cell| highlight(args, cell));
+    for line in board.lines() {
+        out += &format!("    {}\n", line);
+    }
+
+    for reason in reasons(&state, actual_direction, &expected) {
+        out += &format!("  - {}\n", reason);
+    }
+    Some(out)
+}
+
+fn arrow(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Up => "↑",
+        Direction::Down => "↓",
+        Direction::Left => "←",
+        Direction::Right => "→",
+    }
+}
+
+/// Short sentences about the actual move and how the expected ones compare to it
+fn reasons(state: &GameState, actual: Direction, expected: &[Direction]) -> Vec<String> {
+    let mut reasons = vec![];
+    let step = |d: Direction| state.geometry().step(state.you.head, d);
+
+    if let Some(cause) = state.fatal_move(actual) {
+        reasons.push(format!(
+            "The actual move \"{}\" walks into {} at {}",
+            actual,
+            cause,
+            step(actual)
+        ));
+    }
+    for snake in state.losing_head_to_heads(actual) {
+        reasons.push(format!(
+            "The actual move \"{}\" risks a losing head-to-head with \"{}\"",
+            actual, snake.name
+        ));
+    }
+
+    if let Some(before) = state.food_distance(state.you.head) {
+        let after = state.food_distance(step(actual)).unwrap_or(before);
+        if after >= before {
+            reasons.push(format!(
+                "The actual move \"{}\" doesn't get closer to the nearest food, leaving it at distance {}",
+                actual, after
+            ));
+        }
+        for d in expected {
+            let distance = state.food_distance(step(*d)).unwrap_or(before);
+            if distance < before {
+                reasons.push(format!(
+                    "The expected move \"{}\" heads toward the nearest food, at distance {}",
+                    d, distance
+                ));
+            }
+        }
+    }
+
+    let space = state.reachable_space(actual);
+    if let Some((d, best)) = expected
+        .iter()
+        .map(|d| (d, state.reachable_space(*d)))
+        .max_by_key(|(_, space)| *space)
+        .filter(|(_, best)| *best > space)
+    {
+        reasons.push(format!(
+            "The actual move \"{}\" leaves {} reachable cells, \"{}\" would leave {}",
+            actual, space, d, best
+        ));
+    }
+
+    reasons
+}
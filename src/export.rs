@@ -0,0 +1,57 @@
+//! `--export-failures`, which copies the failing tests of a run into a separate
+//! directory to seed a corpus of known failures.
+
+use std::{fs::create_dir_all, path::Path};
+
+use anyhow::Result;
+use log::warn;
+
+use crate::format::{read_test_case_file, write_test_case_file};
+use crate::{Args, TestRun};
+
+/// Writes a copy of every failing test into `dir`, with a description of how it failed
+///
+/// The copies keep their path relative to the test directory. The original files are
+/// only read, and a copy that already exists is left alone.
+pub fn export_failures(args: &Args, dir: &Path, results: &[TestRun]) -> Result<()> {
+    for r in results {
+        let failure = match &r.result {
+            Err(failure) => failure,
+            Ok(()) => continue,
+        };
+        let path = &r.test_case.path;
+        let relative = path
+            .strip_prefix(&args.test_directory)
+            .ok()
+            .or_else(|| path.file_name().map(Path::new))
+            .unwrap_or(path);
+        let output = dir.join(relative);
+        if output.exists() {
+            warn!(
+                "{} already exists, it wasn't exported again",
+                output.display()
+            );
+            continue;
+        }
+
+        let mut test_case_file = read_test_case_file(path)?;
+        let observed = match &r.actual_move {
+            Some(m) => format!(
+                "failed with {}, the snake moved \"{}\"",
+                failure.outcome(),
+                m
+            ),
+            None => format!("failed with {}", failure.outcome()),
+        };
+        test_case_file.description = Some(match test_case_file.description {
+            Some(description) => format!("{} ({} {})", description, path.display(), observed),
+            None => format!("{} {}", path.display(), observed),
+        });
+
+        if let Some(parent) = output.parent() {
+            create_dir_all(parent)?;
+        }
+        write_test_case_file(&output, &test_case_file)?;
+    }
+    Ok(())
+}
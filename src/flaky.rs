@@ -0,0 +1,98 @@
+//! `--repeat`, which runs every test several times to tell the tests that only pass sometimes,
+//! ie because of randomized tie-breaking, from the ones that always fail.
+
+use colored::Colorize;
+
+use crate::{Args, TestFailure, TestRun};
+
+/// How a test did over its `--repeat` runs
+pub struct Repeats {
+    pub passed: u32,
+    pub runs: u32,
+    /// The first failure of a flaky test that `--fail-on-flaky` doesn't fail
+    pub failure: Option<TestFailure>,
+}
+
+impl Repeats {
+    /// Whether the test passed some of its runs but not all of them
+    pub fn is_flaky(&self) -> bool {
+        self.passed > 0 && self.passed < self.runs
+    }
+}
+
+/// Runs a test `runs` times, keeping the first run that failed, or the first one when all of
+/// them passed
+///
+/// A flaky test only fails with `--fail-on-flaky`, otherwise it's kept as a passing run and its
+/// failure is only reported in the list of flaky tests.
+pub fn run_repeated(args: &Args, runs: u32, run: impl Fn() -> TestRun) -> TestRun {
+    let (mut passes, failures): (Vec<_>, Vec<_>) = (0..runs.max(1))
+        .map(|_| run())
+        .partition(|r| r.result.is_ok());
+    let passed = passes.len() as u32;
+    let runs = passed + failures.len() as u32;
+    let repeats = |failure| {
+        Some(Repeats {
+            passed,
+            runs,
+            failure,
+        })
+    };
+
+    let mut test_run = match failures.into_iter().next() {
+        None => passes.remove(0),
+        Some(failed) if passes.is_empty() => failed,
+        Some(failed) if !args.fail_on_flaky => {
+            let mut test_run = passes.remove(0);
+            test_run.repeats = repeats(failed.result.err());
+            return test_run;
+        }
+        Some(mut failed) => {
+            failed.result = failed
+                .result
+                .map_err(|f| TestFailure::Flaky(passed, runs, Box::new(f)));
+            failed
+        }
+    };
+    test_run.repeats = repeats(None);
+    test_run
+}
+
+/// Lists the flaky tests with their pass rates, nothing when there are none
+pub fn print_flaky(args: &Args, results: &[TestRun]) {
+    let flaky: Vec<_> = results
+        .iter()
+        .filter_map(|r| Some((r, r.repeats.as_ref().filter(|r| r.is_flaky())?)))
+        .collect();
+    if flaky.is_empty() {
+        return;
+    }
+
+    println!(
+        "\n{} tests are flaky, they passed some of their runs but not all of them:",
+        flaky.len()
+    );
+    for (r, repeats) in flaky {
+        let failure = repeats
+            .failure
+            .as_ref()
+            .or_else(|| match &r.result {
+                Err(TestFailure::Flaky(_, _, failure)) => Some(failure.as_ref()),
+                _ => None,
+            })
+            .map(|f| format!(", otherwise {}", f.display_failure(args)))
+            .unwrap_or_default();
+        println!(
+            "  - {}: passed {} of {} runs ({}){}",
+            r.test_case.path.display(),
+            repeats.passed,
+            repeats.runs,
+            format!(
+                "{:.0}%",
+                repeats.passed as f64 / repeats.runs as f64 * 100.0
+            )
+            .color(args.failure_color),
+            failure
+        );
+    }
+}
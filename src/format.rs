@@ -0,0 +1,130 @@
+//! The on-disk formats a test case file can be written in.
+
+use std::{
+    fs::{read_to_string, write, File},
+    io::{self, Read, Write},
+    path::Path,
+};
+
+use anyhow::{anyhow, Context, Result};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+
+use crate::TestCaseFile;
+
+/// The path a test read with `--stdin` is reported as
+pub const STDIN_PATH: &str = "<stdin>";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Json,
+    Yaml,
+}
+
+/// Reads and parses a test file, decompressing it first when it ends in `.gz`, or from stdin
+/// for `STDIN_PATH`
+pub fn read_test_case_file(path: &Path) -> Result<TestCaseFile> {
+    let (format, contents) = read_contents(path)?;
+    format.parse(&contents)
+}
+
+/// Reads a file the way `read_test_case_file` does, without expecting it to be a test, ie for a
+/// bare game state
+pub fn read_value(path: &Path) -> Result<serde_json::Value> {
+    let (format, contents) = read_contents(path)?;
+    Ok(match format {
+        FileFormat::Json => serde_json::from_str(&contents)?,
+        FileFormat::Yaml => serde_yaml::from_str(&contents)?,
+    })
+}
+
+fn read_contents(path: &Path) -> Result<(FileFormat, String)> {
+    if path == Path::new(STDIN_PATH) {
+        let mut contents = String::new();
+        io::stdin()
+            .read_to_string(&mut contents)
+            .context("Could not read the test from stdin")?;
+        return Ok((FileFormat::from_path_or_contents(path, &contents), contents));
+    }
+    if path.extension().and_then(|e| e.to_str()) == Some("gz") {
+        let mut contents = String::new();
+        GzDecoder::new(File::open(path)?)
+            .read_to_string(&mut contents)
+            .with_context(|| format!("Could not decompress {}", path.display()))?;
+        let format = FileFormat::from_path_or_contents(&path.with_extension(""), &contents);
+        return Ok((format, contents));
+    }
+
+    let contents = read_to_string(path)?;
+    Ok((FileFormat::from_path_or_contents(path, &contents), contents))
+}
+
+/// Writes a test file back to where it was read from, in the same format
+pub fn write_test_case_file(path: &Path, test_case_file: &TestCaseFile) -> Result<()> {
+    if path.extension().and_then(|e| e.to_str()) == Some("gz") {
+        let contents =
+            FileFormat::from_path(&path.with_extension(""))?.serialize(test_case_file)?;
+        let mut encoder = GzEncoder::new(File::create(path)?, Compression::default());
+        encoder.write_all(contents.as_bytes())?;
+        encoder.finish()?;
+        return Ok(());
+    }
+
+    write(
+        path,
+        FileFormat::from_path(path)?.serialize(test_case_file)?,
+    )?;
+    Ok(())
+}
+
+impl FileFormat {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => Ok(FileFormat::Json),
+            Some("yaml") | Some("yml") => Ok(FileFormat::Yaml),
+            _ => Err(anyhow!(
+                "Could not determine the format of {}, expected a .json, .yaml or .yml file",
+                path.display()
+            )),
+        }
+    }
+
+    /// For files found through a custom `--ext`, ie `.snaketest`, the format is guessed from the contents
+    fn from_path_or_contents(path: &Path, contents: &str) -> Self {
+        FileFormat::from_path(path).unwrap_or_else(|_| {
+            if contents.trim_start().starts_with('{') {
+                FileFormat::Json
+            } else {
+                FileFormat::Yaml
+            }
+        })
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            FileFormat::Json => "json",
+            FileFormat::Yaml => "yaml",
+        }
+    }
+
+    /// The format a file should be converted into
+    pub fn other(self) -> Self {
+        match self {
+            FileFormat::Json => FileFormat::Yaml,
+            FileFormat::Yaml => FileFormat::Json,
+        }
+    }
+
+    pub fn parse(self, contents: &str) -> Result<TestCaseFile> {
+        Ok(match self {
+            FileFormat::Json => serde_json::from_str(contents)?,
+            FileFormat::Yaml => serde_yaml::from_str(contents)?,
+        })
+    }
+
+    pub fn serialize(self, test_case_file: &TestCaseFile) -> Result<String> {
+        Ok(match self {
+            FileFormat::Json => serde_json::to_string_pretty(test_case_file)? + "\n",
+            FileFormat::Yaml => serde_yaml::to_string(test_case_file)?,
+        })
+    }
+}
@@ -0,0 +1,328 @@
+//! The `fuzz` subcommand, which stress tests a server with random variations of a state, or
+//! with random boards when no state is given.
+//!
+//! This is about robustness rather than correctness: any move is accepted, but
+//! errors, timeouts, responses that aren't a valid direction and moves into the
+//! snake's own body are reported and the state that caused them is written out
+//! so it can be reproduced.
+
+use std::{
+    fs::{create_dir_all, write},
+    path::Path,
+};
+
+use anyhow::{anyhow, Result};
+use colored::*;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use serde_json::json;
+use structopt::StructOpt;
+
+use crate::board::{Coord, Direction, GameState, Snake};
+use crate::client::SnakeClient;
+use crate::format::{self, FileFormat};
+use crate::{Args, TestCaseFile};
+
+/// The shape of the boards generated when `fuzz` isn't given a test file
+#[derive(StructOpt, Debug, Clone)]
+pub struct RandomBoards {
+    /// Width of the random boards
+    #[structopt(long, default_value = "11")]
+    width: i32,
+
+    /// Height of the random boards
+    #[structopt(long, default_value = "11")]
+    height: i32,
+
+    /// How many snakes are on a random board, including "you"
+    #[structopt(long, default_value = "4")]
+    snakes: usize,
+
+    /// The share of a random board's free cells that have food, from 0 to 1
+    #[structopt(long, default_value = "0.05")]
+    food_density: f64,
+
+    /// The share of a random board's cells that are hazards, from 0 to 1
+    #[structopt(long, default_value = "0")]
+    hazard_density: f64,
+}
+
+pub fn fuzz(
+    args: &Args,
+    url: &str,
+    seed_file: Option<&Path>,
+    boards: &RandomBoards,
+    count: u32,
+    seed: u64,
+    output_dir: &Path,
+) -> Result<()> {
+    let base = match seed_file {
+        Some(seed_file) => Some(GameState::from_value(
+            &format::read_test_case_file(seed_file)?.state,
+        )?),
+        None => {
+            boards.check()?;
+            None
+        }
+    };
+    let source = match seed_file {
+        Some(seed_file) => seed_file.display().to_string(),
+        None => format!("random {}x{} boards", boards.width, boards.height),
+    };
+
+    let client = SnakeClient::new(args)?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut failures = 0;
+
+    for i in 0..count {
+        let state = match &base {
+            Some(base) => {
+                let mut state = base.clone();
+                mutate(&mut state, &mut rng);
+                state
+            }
+            None => boards.generate(&mut rng)?,
+        };
+
+        let problem = match client.request_move(url, &state.to_value()?) {
+            Ok(response) => match response.r#move.parse::<Direction>() {
+                Ok(d) if hits_own_body(&state, d) => {
+                    format!("moved {} into its own body", d)
+                }
+                Ok(_) => continue,
+                Err(e) => e.to_string(),
+            },
+            Err(e) => e.to_string(),
+        };
+        let state = state.to_value()?;
+        failures += 1;
+
+        create_dir_all(output_dir)?;
+        let path = output_dir.join(format!("fuzz_{}_{}.json", seed, i));
+        let reproduction = TestCaseFile {
+            state,
+            expected: Direction::ALL
+                .iter()
+                .map(|d| d.to_string().into())
+                .collect(),
+            description: Some(format!(
+                "Generated by fuzzing {} with seed {}: {}",
+                source, seed, problem
+            )),
+            ..Default::default()
+        };
+        write(&path, FileFormat::Json.serialize(&reproduction)?)?;
+
+        println!(
+            "{} {}: {}\nState written to {}\n",
+            "Failure on variation".color(args.failure_color),
+            i,
+            problem,
+            path.display()
+        );
+    }
+
+    println!(
+        "{} out of {} variations were handled without errors (seed {})",
+        count - failures,
+        count,
+        seed
+    );
+
+    if failures > 0 {
+        std::process::exit(1)
+    }
+
+    Ok(())
+}
+
+/// Whether the move takes the head into a cell of its body that's still there next turn, when
+/// another move would have stayed on the board and clear of every snake
+fn hits_own_body(state: &GameState, direction: Direction) -> bool {
+    let geometry = state.geometry();
+    let next = |d: Direction| geometry.step(state.you.head, d);
+    let is_safe = |d: Direction| {
+        state.board.contains(next(d))
+            && !state
+                .board
+                .snakes
+                .iter()
+                .any(|s| still_there(s).contains(&next(d)))
+    };
+    still_there(&state.you).contains(&next(direction)) && Direction::ALL.iter().any(|d| is_safe(*d))
+}
+
+/// The cells of the body that are still taken after the snake moves, which is all but the tail
+/// unless the snake just ate and its tail is stacked
+fn still_there(snake: &Snake) -> &[Coord] {
+    let body = &snake.body;
+    let stacked = body.len() >= 2 && body[body.len() - 1] == body[body.len() - 2];
+    if stacked {
+        body
+    } else {
+        &body[..body.len().saturating_sub(1)]
+    }
+}
+
+impl RandomBoards {
+    fn check(&self) -> Result<()> {
+        if self.width < 1 || self.height < 1 {
+            return Err(anyhow!("--width and --height have to be at least 1"));
+        }
+        if self.snakes < 1 || self.snakes as i32 > self.width * self.height {
+            return Err(anyhow!(
+                "--snakes has to be between 1 and the {} cells of the board",
+                self.width * self.height
+            ));
+        }
+        for (name, density) in [
+            ("--food-density", self.food_density),
+            ("--hazard-density", self.hazard_density),
+        ] {
+            if !(0.0..=1.0).contains(&density) {
+                return Err(anyhow!("{} has to be between 0 and 1", name));
+            }
+        }
+        Ok(())
+    }
+
+    /// A board of the configured size with random snakes, food and hazards
+    ///
+    /// Each snake is a random walk from its head over the free cells, the first one being "you".
+    /// A snake that gets boxed in is left shorter than it was meant to be.
+    fn generate(&self, rng: &mut StdRng) -> Result<GameState> {
+        let mut state = GameState::from_value(&json!({
+            "game": { "id": "fuzz", "ruleset": { "name": "standard", "version": "" }, "timeout": 500 },
+            "turn": rng.gen_range(0..300),
+            "board": { "width": self.width, "height": self.height, "food": [], "hazards": [], "snakes": [] },
+            "you": { "id": "", "health": 0, "body": [], "head": { "x": 0, "y": 0 } },
+        }))?;
+
+        for i in 0..self.snakes {
+            let head = match free_cells(&state).choose(rng) {
+                Some(head) => *head,
+                None => break,
+            };
+            let mut body = vec![head];
+            for _ in 1..rng.gen_range(3..=8) {
+                let last = body[body.len() - 1];
+                let free = free_cells(&state);
+                let next: Vec<_> = Direction::ALL
+                    .iter()
+                    .map(|d| last.moved(*d))
+                    .filter(|c| free.contains(c) && !body.contains(c))
+                    .collect();
+                match next.choose(rng) {
+                    Some(c) => body.push(*c),
+                    None => break,
+                }
+            }
+            let id = if i == 0 {
+                "fuzz-you".to_owned()
+            } else {
+                format!("fuzz-snake-{}", i)
+            };
+            state.board.snakes.push(Snake {
+                id: id.clone(),
+                name: id,
+                health: rng.gen_range(1..=100),
+                length: body.len(),
+                head,
+                body,
+                extra: Default::default(),
+            });
+        }
+        state.you = state.board.snakes[0].clone();
+
+        let mut cells = free_cells(&state);
+        cells.shuffle(rng);
+        let food = (cells.len() as f64 * self.food_density).round() as usize;
+        state.board.food = cells.into_iter().take(food).collect();
+
+        let mut cells: Vec<_> = (0..self.width)
+            .flat_map(|x| (0..self.height).map(move |y| Coord { x, y }))
+            .collect();
+        cells.shuffle(rng);
+        let hazards = (cells.len() as f64 * self.hazard_density).round() as usize;
+        state.board.hazards = cells.into_iter().take(hazards).collect();
+
+        Ok(state)
+    }
+}
+
+/// Applies a random selection of mutations, keeping the state something the engine could send
+fn mutate(state: &mut GameState, rng: &mut StdRng) {
+    if rng.gen_bool(0.5) {
+        shift_food(state, rng);
+    }
+    if rng.gen_bool(0.3) {
+        remove_opponent(state, rng);
+    }
+    if rng.gen_bool(0.3) {
+        add_opponent(state, rng);
+    }
+    if rng.gen_bool(0.5) {
+        for snake in &mut state.board.snakes {
+            snake.health = rng.gen_range(1..=100);
+        }
+    }
+
+    if let Some(you) = state.board.snakes.iter().find(|s| s.id == state.you.id) {
+        state.you = you.clone();
+    }
+}
+
+fn free_cells(state: &GameState) -> Vec<Coord> {
+    let board = &state.board;
+    let mut cells = vec![];
+    for x in 0..board.width {
+        for y in 0..board.height {
+            let c = Coord { x, y };
+            let occupied =
+                board.food.contains(&c) || board.snakes.iter().any(|s| s.body.contains(&c));
+            if !occupied {
+                cells.push(c);
+            }
+        }
+    }
+    cells
+}
+
+fn shift_food(state: &mut GameState, rng: &mut StdRng) {
+    let food_count = state.board.food.len();
+    state.board.food.clear();
+    let mut cells = free_cells(state);
+    cells.shuffle(rng);
+    state.board.food = cells.into_iter().take(food_count).collect();
+}
+
+fn remove_opponent(state: &mut GameState, rng: &mut StdRng) {
+    let opponents: Vec<usize> = state
+        .board
+        .snakes
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.id != state.you.id)
+        .map(|(i, _)| i)
+        .collect();
+    if let Some(i) = opponents.choose(rng) {
+        state.board.snakes.remove(*i);
+    }
+}
+
+/// Adds a new snake the way the engine spawns them, with its whole body stacked on one cell
+fn add_opponent(state: &mut GameState, rng: &mut StdRng) {
+    let cell = match free_cells(state).choose(rng) {
+        Some(cell) => *cell,
+        None => return,
+    };
+    let id = format!("fuzz-snake-{}", state.board.snakes.len());
+    state.board.snakes.push(Snake {
+        id: id.clone(),
+        name: id,
+        health: 100,
+        body: vec![cell; 3],
+        head: cell,
+        length: 3,
+        extra: Default::default(),
+    });
+}
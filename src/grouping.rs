@@ -0,0 +1,76 @@
+//! `--group-failures-by`, which sections the failure details of the report.
+
+use std::{collections::BTreeMap, str::FromStr};
+
+use anyhow::{anyhow, Result};
+
+use crate::TestRun;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureGrouping {
+    /// In the order the test files were run, without sections
+    Path,
+    Description,
+    /// A test with several tags is listed under each of them
+    Tag,
+    /// The kind of failure, ie `incorrect_move`
+    Reason,
+}
+
+impl FromStr for FailureGrouping {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "path" => Ok(FailureGrouping::Path),
+            "description" => Ok(FailureGrouping::Description),
+            "tag" => Ok(FailureGrouping::Tag),
+            "reason" => Ok(FailureGrouping::Reason),
+            _ => Err(anyhow!(
+                "Unknown grouping \"{}\", expected path, description, tag or reason",
+                s
+            )),
+        }
+    }
+}
+
+/// The failed tests under a heading for each group, sorted by heading
+///
+/// Tests without a description or tags are grouped last.
+pub fn group_failures(
+    grouping: FailureGrouping,
+    results: &[TestRun],
+) -> Vec<(String, Vec<&TestRun>)> {
+    // Keyed by whether the group is for tests missing the field first, so those sort last
+    let mut groups: BTreeMap<(bool, String), Vec<&TestRun>> = BTreeMap::new();
+    for r in results {
+        let failure = match &r.result {
+            Err(failure) => failure,
+            Ok(()) => continue,
+        };
+        let headings = match grouping {
+            FailureGrouping::Path => vec![(false, "All failures".to_owned())],
+            FailureGrouping::Description => match &r.test_case.description {
+                Some(description) => vec![(false, description.clone())],
+                None => vec![(true, "No description".to_owned())],
+            },
+            FailureGrouping::Tag if r.test_case.tags.is_empty() => {
+                vec![(true, "Untagged".to_owned())]
+            }
+            FailureGrouping::Tag => r
+                .test_case
+                .tags
+                .iter()
+                .map(|t| (false, t.clone()))
+                .collect(),
+            FailureGrouping::Reason => vec![(false, failure.outcome().to_owned())],
+        };
+        for heading in headings {
+            groups.entry(heading).or_default().push(r);
+        }
+    }
+    groups
+        .into_iter()
+        .map(|((_, heading), runs)| (heading, runs))
+        .collect()
+}
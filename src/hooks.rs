@@ -0,0 +1,132 @@
+//! `--pre-cmd` and `--post-cmd`, starting the snake before the suite and stopping it after, so
+//! a run doesn't need a wrapper script to bring the server up.
+
+use std::{
+    process::{Child, Command, Stdio},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context, Result};
+use log::{debug, info};
+
+use crate::client::SnakeClient;
+use crate::{info::base_url, required_url, Args};
+
+/// How long to wait between checks of whether the server has come up
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The server started by `--pre-cmd`, stopped and followed by `--post-cmd` when it's dropped
+pub struct Hooks {
+    server: Option<Child>,
+    post_cmd: Option<String>,
+}
+
+impl Hooks {
+    /// Starts `--pre-cmd` and waits until the server answers at `--url`
+    ///
+    /// Without a `--pre-cmd` nothing is started, and `--post-cmd` still runs at the end.
+    pub fn start(args: &Args) -> Result<Hooks> {
+        let mut hooks = Hooks {
+            server: None,
+            post_cmd: args.post_cmd.clone(),
+        };
+        let pre_cmd = match &args.pre_cmd {
+            Some(pre_cmd) => pre_cmd,
+            None => return Ok(hooks),
+        };
+        let url = required_url(args)?;
+
+        info!("Starting {}", pre_cmd);
+        let mut command = shell(pre_cmd);
+        // The server's logs go to stderr, so they don't end up in the report or `--jsonl`
+        command.stdout(Stdio::from(std::io::stderr()));
+        // Its own process group, so the server `cargo run` starts is stopped along with cargo
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        let server = hooks.server.insert(
+            command
+                .spawn()
+                .with_context(|| format!("Could not run --pre-cmd {}", pre_cmd))?,
+        );
+
+        let client = SnakeClient::new(args)?;
+        let ready_url = format!("{}/", base_url(url));
+        let timeout = Duration::from_millis(args.ready_timeout_ms);
+        let started = Instant::now();
+        loop {
+            if let Some(status) = server.try_wait()? {
+                return Err(anyhow!(
+                    "--pre-cmd {} exited with {} before the server answered at {}",
+                    pre_cmd,
+                    status,
+                    ready_url
+                ));
+            }
+            match client.client.get(&ready_url).send() {
+                Ok(response) => {
+                    debug!("{} answered with {}", ready_url, response.status());
+                    return Ok(hooks);
+                }
+                Err(e) if started.elapsed() < timeout => debug!("Not up yet: {}", e),
+                Err(e) => {
+                    return Err(anyhow!(
+                        "The server started by --pre-cmd didn't answer at {} within {}ms, pass a longer --ready-timeout-ms if it's still building: {}",
+                        ready_url,
+                        args.ready_timeout_ms,
+                        e
+                    ))
+                }
+            }
+            sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+impl Drop for Hooks {
+    fn drop(&mut self) {
+        if let Some(mut server) = self.server.take() {
+            info!("Stopping the server started by --pre-cmd");
+            stop(&mut server);
+        }
+        if let Some(post_cmd) = &self.post_cmd {
+            info!("Running {}", post_cmd);
+            match shell(post_cmd).status() {
+                Ok(status) if status.success() => {}
+                Ok(status) => eprintln!("--post-cmd {} exited with {}", post_cmd, status),
+                Err(e) => eprintln!("Could not run --post-cmd {}: {}", post_cmd, e),
+            }
+        }
+    }
+}
+
+fn shell(cmd: &str) -> Command {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c");
+        command
+    };
+    command.arg(cmd);
+    command
+}
+
+/// Asks the whole process group to terminate, then waits for the command itself
+fn stop(server: &mut Child) {
+    if let Ok(Some(_)) = server.try_wait() {
+        return;
+    }
+    let terminated = cfg!(unix)
+        && Command::new("kill")
+            .args(["-TERM", "--", &format!("-{}", server.id())])
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success());
+    if !terminated {
+        let _ = server.kill();
+    }
+    let _ = server.wait();
+}
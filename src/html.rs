@@ -0,0 +1,227 @@
+//! `--report-html`, a single self-contained HTML file with the results of a run and a drawing
+//! of the board of every failed test, for sharing with people who don't run the tool.
+
+use std::{fs::write, path::Path, time::Duration};
+
+use anyhow::{Context, Result};
+
+use crate::board::{Coord, Direction, GameState};
+use crate::junit::escape;
+use crate::{Args, LoadError, TestCase, TestRun};
+
+/// Pixels per cell of the drawn boards
+const CELL: i32 = 24;
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;width:100%}\
+th,td{border:1px solid #ccc;padding:4px 8px;text-align:left;vertical-align:top}\
+th{background:#eee}\
+.passed{color:#2e7d32}.failed{color:#c62828}.skipped{color:#777}\
+.failure{margin:2em 0;padding-top:1em;border-top:1px solid #ccc}\
+pre{white-space:pre-wrap}";
+
+/// The colors of the other snakes, `you` is always green
+const SNAKE_COLORS: [&str; 4] = ["#1565c0", "#6a1b9a", "#ef6c00", "#00838f"];
+
+/// Writes a row per test, with skipped tests and files that couldn't be loaded included so the
+/// totals match the report, followed by the details of every failure
+pub fn write_html(
+    args: &Args,
+    path: &Path,
+    results: &[TestRun],
+    skipped: &[TestCase],
+    load_errors: &[LoadError],
+) -> Result<()> {
+    let colorized = colored::control::SHOULD_COLORIZE.should_colorize();
+    colored::control::set_override(false);
+    let rows: String = results.iter().map(|r| row(args, r)).collect();
+    let failures: String = results
+        .iter()
+        .filter(|r| r.result.is_err())
+        .map(|r| failure(args, r))
+        .collect();
+    colored::control::set_override(colorized);
+
+    let passed = results.iter().filter(|r| r.result.is_ok()).count();
+    let time: Duration = results.iter().filter_map(|r| r.duration).sum();
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>battlesnake_tests report</title>\n<style>{}</style>\n</head>\n<body>\n<h1>{} out of {} tests passed</h1>\n<p>{} skipped, {} could not be loaded, {:.2}s spent on requests</p>\n",
+        STYLE,
+        passed,
+        results.len() + load_errors.len(),
+        skipped.len(),
+        load_errors.len(),
+        time.as_secs_f64()
+    );
+    html += "<table>\n<tr><th>Test</th><th>Result</th><th>Description</th><th>Expected</th><th>Actual</th><th>Latency</th></tr>\n";
+    html += &rows;
+    for test_case in skipped {
+        html += &format!(
+            "<tr><td>{}</td><td class=\"skipped\">skipped</td><td>{}</td><td></td><td></td><td></td></tr>\n",
+            escape(&test_case.path.display().to_string()),
+            escape(test_case.description.as_deref().unwrap_or_default())
+        );
+    }
+    for load_error in load_errors {
+        html += &format!(
+            "<tr><td>{}</td><td class=\"failed\">load_error</td><td colspan=\"4\">{}</td></tr>\n",
+            escape(&load_error.path.display().to_string()),
+            escape(&format!("{:#}", load_error.error))
+        );
+    }
+    html += "</table>\n";
+    html += &failures;
+    html += "</body>\n</html>\n";
+
+    write(path, html).with_context(|| format!("Could not write the HTML report {}", path.display()))
+}
+
+fn row(args: &Args, run: &TestRun) -> String {
+    let test_case = &run.test_case;
+    let path = escape(&test_case.path.display().to_string());
+    let (class, outcome) = match &run.result {
+        Ok(()) => ("passed", "passed"),
+        Err(failure) => ("failed", failure.outcome()),
+    };
+    let name = match run.result {
+        Ok(()) => path,
+        Err(_) => format!("<a href=\"#{}\">{}</a>", anchor(run), path),
+    };
+    format!(
+        "<tr><td>{}</td><td class=\"{}\">{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+        name,
+        class,
+        outcome,
+        escape(test_case.description.as_deref().unwrap_or_default()),
+        escape(&expected_moves(test_case)),
+        escape(run.actual_move.as_deref().unwrap_or_default()),
+        run.duration.map(|d| args.latency(d)).unwrap_or_default()
+    )
+}
+
+/// `up, left`, `any` when every move passes, with the forbidden moves after it
+pub fn expected_moves(test_case: &TestCase) -> String {
+    let expected = if test_case.expected.is_empty() {
+        "any".to_owned()
+    } else {
+        test_case.expected.join(", ")
+    };
+    if test_case.forbidden.is_empty() {
+        expected
+    } else {
+        format!("{}, not {}", expected, test_case.forbidden.join(", "))
+    }
+}
+
+/// Failures are linked by their path, with the characters that aren't valid in an id replaced
+fn anchor(run: &TestRun) -> String {
+    let id: String = run
+        .test_case
+        .path
+        .display()
+        .to_string()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    format!("failure-{}", id)
+}
+
+fn failure(args: &Args, run: &TestRun) -> String {
+    let message = match &run.result {
+        Err(failure) => failure.display_failure(args),
+        Ok(()) => return String::new(),
+    };
+    let board = match GameState::from_value(&run.test_case.state) {
+        Ok(state) => board_svg(&state, &run.test_case.expected, run.actual_move.as_deref()),
+        Err(e) => format!(
+            "<p>The state could not be drawn: {}</p>",
+            escape(&e.to_string())
+        ),
+    };
+    format!(
+        "<div class=\"failure\" id=\"{}\">\n<h2>{}</h2>\n{}<pre>{}</pre>\n{}\n</div>\n",
+        anchor(run),
+        escape(&run.test_case.path.display().to_string()),
+        run.test_case
+            .description
+            .as_deref()
+            .map(|d| format!("<p>{}</p>\n", escape(d)))
+            .unwrap_or_default(),
+        escape(&message),
+        board
+    )
+}
+
+/// An SVG of the board with the cells the expected moves lead to outlined in green, and the one
+/// the snake moved into in red
+fn board_svg(state: &GameState, expected: &[String], actual: Option<&str>) -> String {
+    let board = &state.board;
+    let (width, height) = (board.width.max(0), board.height.max(0));
+    // The engine's y grows upwards, an SVG's downwards
+    let corner = |c: Coord| (c.x * CELL, (height - 1 - c.y) * CELL);
+    let rect = |c: Coord, style: &str| {
+        let (x, y) = corner(c);
+        format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" {}/>",
+            x, y, CELL, CELL, style
+        )
+    };
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">",
+        width * CELL,
+        height * CELL,
+        width * CELL,
+        height * CELL
+    );
+    for x in 0..width {
+        for y in 0..height {
+            svg += &rect(Coord { x, y }, "fill=\"#f5f5f5\" stroke=\"#ddd\"");
+        }
+    }
+    for hazard in &board.hazards {
+        svg += &rect(*hazard, "fill=\"#9e9e9e\" fill-opacity=\"0.6\"");
+    }
+    for food in &board.food {
+        let (x, y) = corner(*food);
+        svg += &format!(
+            "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"#e53935\"/>",
+            x + CELL / 2,
+            y + CELL / 2,
+            CELL / 4
+        );
+    }
+    let mut others = SNAKE_COLORS.iter().cycle();
+    for snake in &board.snakes {
+        let color = if snake.id == state.you.id {
+            "#2e7d32"
+        } else {
+            others.next().copied().unwrap_or_default()
+        };
+        for cell in snake.body.iter().rev() {
+            svg += &rect(*cell, &format!("fill=\"{}\" fill-opacity=\"0.7\"", color));
+        }
+        svg += &rect(
+            snake.head,
+            &format!("fill=\"{}\" stroke=\"#000\" stroke-width=\"2\"", color),
+        );
+    }
+
+    let geometry = state.geometry();
+    let target = |m: &str| {
+        m.parse::<Direction>()
+            .ok()
+            .map(|d| geometry.step(state.you.head, d))
+    };
+    for cell in expected.iter().filter_map(|m| target(m)) {
+        svg += &rect(
+            cell,
+            "fill=\"none\" stroke=\"#2e7d32\" stroke-width=\"3\" stroke-dasharray=\"4 2\"",
+        );
+    }
+    if let Some(cell) = actual.and_then(target) {
+        svg += &rect(cell, "fill=\"none\" stroke=\"#c62828\" stroke-width=\"3\"");
+    }
+    svg += "</svg>";
+    svg
+}
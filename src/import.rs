@@ -0,0 +1,61 @@
+//! The `record` subcommand, which turns the turns of a finished game on the engine into
+//! new test files.
+//!
+//! The tests are written without expected moves, so the loader skips them until they've
+//! been filled in.
+
+use std::{fs::create_dir_all, path::Path};
+
+use anyhow::{anyhow, Result};
+
+use crate::engine;
+use crate::format::write_test_case_file;
+use crate::{Args, TestCaseFile};
+
+pub fn record_game(
+    args: &Args,
+    engine_url: &str,
+    game_id: &str,
+    snake: &str,
+    turns: &[u32],
+) -> Result<()> {
+    let export = engine::fetch_export(engine_url, game_id)?;
+    let you_id = export
+        .find_snake_id(snake)
+        .ok_or_else(|| anyhow!("No snake with the id or name \"{}\" in this game", snake))?;
+
+    create_dir_all(&args.test_directory)?;
+    let mut written = 0;
+    for frame in &export.frames {
+        if !turns.is_empty() && !turns.contains(&frame.turn) {
+            continue;
+        }
+        let state = match frame.to_state(&export.game, &you_id) {
+            Some(state) => state,
+            None => break,
+        };
+
+        let path = Path::new(&args.test_directory)
+            .join(format!("{}_turn_{:03}.json", game_id, frame.turn));
+        if path.exists() {
+            println!("{} already exists, leaving it as it is", path.display());
+            continue;
+        }
+        let test_case_file = TestCaseFile {
+            state: state.to_value()?,
+            description: Some(format!(
+                "TODO: turn {} of game {}, fill in the expected moves",
+                frame.turn, game_id
+            )),
+            ..Default::default()
+        };
+        write_test_case_file(&path, &test_case_file)?;
+        written += 1;
+    }
+
+    println!(
+        "Wrote {} test files to {}, fill in their expected moves",
+        written, args.test_directory
+    );
+    Ok(())
+}
@@ -0,0 +1,143 @@
+//! The snake's root `GET /` endpoint, which reports its API version and customizations.
+
+use anyhow::{anyhow, Result};
+use log::debug;
+use serde_json::Value;
+
+use crate::api::{type_name, ApiVersion};
+use crate::client::SnakeClient;
+
+#[derive(Debug)]
+pub struct BattlesnakeInfo {
+    pub apiversion: Option<String>,
+    pub author: Option<String>,
+    pub color: Option<String>,
+    pub head: Option<String>,
+    pub tail: Option<String>,
+    /// The whole response, for checking the fields that aren't strings
+    pub raw: Value,
+}
+
+impl BattlesnakeInfo {
+    /// Reads the fields that are strings, anything else is left for `check_info` to report
+    fn from_value(raw: Value) -> Self {
+        let field = |key: &str| raw.get(key).and_then(Value::as_str).map(str::to_owned);
+        BattlesnakeInfo {
+            apiversion: field("apiversion"),
+            author: field("author"),
+            color: field("color"),
+            head: field("head"),
+            tail: field("tail"),
+            raw,
+        }
+    }
+}
+
+/// The root of the snake's API, given the URL its moves are requested from
+///
+/// `--url` points at the move endpoint, so a trailing `/move` is removed.
+pub fn base_url(move_url: &str) -> &str {
+    let url = move_url.trim_end_matches('/');
+    url.strip_suffix("/move").unwrap_or(url)
+}
+
+pub fn fetch_info(client: &SnakeClient, move_url: &str) -> Result<BattlesnakeInfo> {
+    let url = format!("{}/", base_url(move_url));
+    debug!("GET {}", url);
+    let raw: Value = client.client.get(&url).send()?.error_for_status()?.json()?;
+    if !raw.is_object() {
+        return Err(anyhow!(
+            "GET {} returned {}, an object was expected",
+            url,
+            type_name(&raw)
+        ));
+    }
+    Ok(BattlesnakeInfo::from_value(raw))
+}
+
+/// Fails when the server doesn't report the API version the tests were written for
+pub fn check_apiversion(info: &BattlesnakeInfo, expected: &str) -> Result<()> {
+    match info.apiversion.as_deref() {
+        Some(actual) if actual == expected => Ok(()),
+        Some(actual) => Err(anyhow!(
+            "The server reports apiversion \"{}\" but \"{}\" was expected, so none of the tests were run",
+            actual,
+            expected
+        )),
+        None => Err(anyhow!(
+            "The server did not report an apiversion but \"{}\" was expected, so none of the tests were run",
+            expected
+        )),
+    }
+}
+
+/// The fields `--check-info` requires, every one of them has to be a string
+const FIELDS: [&str; 5] = ["apiversion", "author", "color", "head", "tail"];
+
+/// The `--check-info` preflight, failing with every problem found in the server's info
+///
+/// Every field has to be there and be a string, `apiversion` has to be one this tool knows,
+/// `color` a `#RRGGBB` hex color and `author` can't be empty. `head` and `tail` have to match
+/// `--expect-head` and `--expect-tail` when they're given, and can't be empty strings otherwise.
+pub fn check_info(
+    info: &BattlesnakeInfo,
+    expect_head: Option<&str>,
+    expect_tail: Option<&str>,
+) -> Result<()> {
+    let mut problems: Vec<_> = FIELDS
+        .iter()
+        .filter_map(|field| match info.raw.get(field) {
+            None => Some(format!("{} is missing", field)),
+            Some(Value::String(_)) => None,
+            Some(value) => Some(format!("{} is {}, not a string", field, type_name(value))),
+        })
+        .collect();
+
+    if let Some(apiversion) = &info.apiversion {
+        if let Err(e) = apiversion.parse::<ApiVersion>() {
+            problems.push(format!("apiversion: {}", e));
+        }
+    }
+    if info.author.as_deref() == Some("") {
+        problems.push("author is an empty string".to_owned());
+    }
+    if let Some(color) = info.color.as_deref().filter(|c| !is_hex_color(c)) {
+        problems.push(format!(
+            "color is \"{}\", but a hex color like \"#ff00aa\" was expected",
+            color
+        ));
+    }
+    problems.extend(
+        [
+            ("head", info.head.as_deref(), expect_head),
+            ("tail", info.tail.as_deref(), expect_tail),
+        ]
+        .iter()
+        .filter_map(|(field, actual, expected)| match (actual, expected) {
+            (Some(actual), Some(expected)) if actual == expected => None,
+            (actual, Some(expected)) => Some(format!(
+                "{} is {} but \"{}\" was expected",
+                field,
+                actual.map_or_else(|| "missing".to_owned(), |a| format!("\"{}\"", a)),
+                expected
+            )),
+            (Some(""), None) => Some(format!("{} is an empty string", field)),
+            _ => None,
+        }),
+    );
+    if problems.is_empty() {
+        return Ok(());
+    }
+
+    Err(anyhow!(
+        "The server's info failed the checks, so none of the tests were run: {}",
+        problems.join("; ")
+    ))
+}
+
+/// `#` followed by six hex digits, as the Battlesnake API documents colors
+fn is_hex_color(color: &str) -> bool {
+    color
+        .strip_prefix('#')
+        .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
@@ -0,0 +1,81 @@
+//! Streaming results as JSON Lines with `--jsonl`, one object per test as it finishes.
+
+use std::{
+    fs::File,
+    io::{self, Write},
+    path::Path,
+};
+
+use anyhow::Result;
+use serde_json::json;
+
+use crate::{Args, LoadError, TestRun};
+
+pub struct JsonlWriter {
+    out: Box<dyn Write>,
+}
+
+impl JsonlWriter {
+    /// Writes to the given file, or to stdout when there is none
+    pub fn new(path: Option<&Path>) -> Result<Self> {
+        let out: Box<dyn Write> = match path {
+            Some(path) => Box::new(File::create(path)?),
+            None => Box::new(io::stdout()),
+        };
+        Ok(JsonlWriter { out })
+    }
+
+    pub fn write_run(&mut self, args: &Args, run: &TestRun) -> Result<()> {
+        let (outcome, message) = match &run.result {
+            Ok(()) => ("passed", None),
+            Err(f) => {
+                let colorized = colored::control::SHOULD_COLORIZE.should_colorize();
+                colored::control::set_override(false);
+                let message = f.display_failure(args);
+                colored::control::set_override(colorized);
+                (f.outcome(), Some(message))
+            }
+        };
+
+        self.write_line(&json!({
+            "type": "test",
+            "path": run.test_case.path,
+            "description": run.test_case.description,
+            "outcome": outcome,
+            "message": message,
+            "expected": run.test_case.expected,
+            "actual": run.actual_move,
+            "latency_ms": run.duration.map(|d| d.as_secs_f64() * 1000.0),
+            "last_attempt_ms": run.attempts.map(|a| a.last.as_secs_f64() * 1000.0),
+            "attempts": run.attempts.map(|a| a.count),
+            "warnings": run.warnings,
+        }))
+    }
+
+    pub fn write_load_error(&mut self, load_error: &LoadError) -> Result<()> {
+        self.write_line(&json!({
+            "type": "load_error",
+            "path": load_error.path,
+            "message": format!("{:#}", load_error.error),
+        }))
+    }
+
+    pub fn write_summary(&mut self, results: &[TestRun], load_errors: &[LoadError]) -> Result<()> {
+        let passed = results.iter().filter(|r| r.result.is_ok()).count();
+        self.write_line(&json!({
+            "type": "summary",
+            "passed": passed,
+            "failed": results.len() - passed,
+            "load_errors": load_errors.len(),
+            "total": results.len() + load_errors.len(),
+        }))
+    }
+
+    /// Each object is written and flushed in one go so lines are never interleaved
+    fn write_line(&mut self, value: &serde_json::Value) -> Result<()> {
+        let line = format!("{}\n", value);
+        self.out.write_all(line.as_bytes())?;
+        self.out.flush()?;
+        Ok(())
+    }
+}
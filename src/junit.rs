@@ -0,0 +1,112 @@
+//! `--report-junit`, a JUnit XML file for CI systems that show test results, ie GitHub Actions
+//! and GitLab.
+
+use std::{fs::write, path::Path, time::Duration};
+
+use anyhow::{Context, Result};
+
+use crate::{Args, LoadError, TestCase, TestFailure, TestRun};
+
+/// Writes a `<testcase>` per test file, named by its path, with skipped tests and files that
+/// couldn't be loaded included so the totals match the report
+pub fn write_junit(
+    args: &Args,
+    path: &Path,
+    results: &[TestRun],
+    skipped: &[TestCase],
+    load_errors: &[LoadError],
+) -> Result<()> {
+    let colorized = colored::control::SHOULD_COLORIZE.should_colorize();
+    colored::control::set_override(false);
+    let cases: Vec<_> = results.iter().map(|r| test_case(args, r)).collect();
+    colored::control::set_override(colorized);
+
+    let failures = results
+        .iter()
+        .filter(|r| matches!(&r.result, Err(f) if !matches!(f, TestFailure::Error(_) | TestFailure::Timeout(_))))
+        .count();
+    let errors = results
+        .iter()
+        .filter(|r| {
+            matches!(
+                r.result,
+                Err(TestFailure::Error(_) | TestFailure::Timeout(_))
+            )
+        })
+        .count()
+        + load_errors.len();
+    let time: Duration = results.iter().filter_map(|r| r.duration).sum();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml += &format!(
+        "<testsuites tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" skipped=\"{skipped}\" time=\"{time:.3}\">\n  <testsuite name=\"battlesnake_tests\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\" skipped=\"{skipped}\" time=\"{time:.3}\">\n",
+        tests = results.len() + skipped.len() + load_errors.len(),
+        failures = failures,
+        errors = errors,
+        skipped = skipped.len(),
+        time = time.as_secs_f64(),
+    );
+    for case in cases {
+        xml += &case;
+    }
+    for test_case in skipped {
+        xml += &format!(
+            "    <testcase name=\"{}\" classname=\"battlesnake_tests\" time=\"0\">\n      <skipped/>\n    </testcase>\n",
+            escape(&test_case.path.display().to_string())
+        );
+    }
+    for load_error in load_errors {
+        xml += &format!(
+            "    <testcase name=\"{}\" classname=\"battlesnake_tests\" time=\"0\">\n      <error type=\"load_error\" message=\"{}\"/>\n    </testcase>\n",
+            escape(&load_error.path.display().to_string()),
+            escape(&format!("{:#}", load_error.error))
+        );
+    }
+    xml += "  </testsuite>\n</testsuites>\n";
+
+    write(path, xml).with_context(|| format!("Could not write the JUnit report {}", path.display()))
+}
+
+fn test_case(args: &Args, run: &TestRun) -> String {
+    let test_case = &run.test_case;
+    let mut xml = format!(
+        "    <testcase name=\"{}\" classname=\"battlesnake_tests\" time=\"{:.3}\">\n",
+        escape(&test_case.path.display().to_string()),
+        run.duration.unwrap_or_default().as_secs_f64()
+    );
+    if let Some(description) = &test_case.description {
+        xml += &format!(
+            "      <properties>\n        <property name=\"description\" value=\"{}\"/>\n      </properties>\n",
+            escape(description)
+        );
+    }
+    if let Err(failure) = &run.result {
+        let element = match failure {
+            TestFailure::Error(_) | TestFailure::Timeout(_) => "error",
+            _ => "failure",
+        };
+        let message = failure.display_failure(args);
+        let body = match &test_case.description {
+            Some(description) => format!("Description: {}\n{}", description, message),
+            None => message.clone(),
+        };
+        xml += &format!(
+            "      <{element} type=\"{}\" message=\"{}\">{}</{element}>\n",
+            failure.outcome(),
+            escape(&message),
+            escape(&body),
+            element = element
+        );
+    }
+    xml += "    </testcase>\n";
+    xml
+}
+
+/// Escapes the characters that are special in XML, and so in HTML
+pub fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
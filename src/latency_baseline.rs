@@ -0,0 +1,77 @@
+//! A saved record of how long each test took, so a later run can flag tests that
+//! got slower even though they still pass.
+
+use std::{
+    collections::BTreeMap,
+    fs::{read_to_string, write},
+    path::Path,
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use colored::*;
+
+use crate::{Args, TestRun};
+
+/// Milliseconds per test path
+type Baseline = BTreeMap<String, f64>;
+
+pub fn save(path: &Path, results: &[TestRun]) -> Result<()> {
+    let baseline: Baseline = results
+        .iter()
+        .filter_map(|r| {
+            let duration = r.duration?;
+            Some((
+                r.test_case.path.display().to_string(),
+                duration.as_secs_f64() * 1000.0,
+            ))
+        })
+        .collect();
+    write(path, serde_json::to_string_pretty(&baseline)? + "\n")?;
+    Ok(())
+}
+
+/// Prints the tests that are more than `threshold` percent slower than the baseline, worst first
+///
+/// Returns whether any test regressed.
+pub fn compare(args: &Args, path: &Path, threshold: f64, results: &[TestRun]) -> Result<bool> {
+    let baseline: Baseline = serde_json::from_str(&read_to_string(path)?)
+        .with_context(|| format!("Could not read the latency baseline {}", path.display()))?;
+
+    let mut regressions: Vec<_> = results
+        .iter()
+        .filter_map(|r| {
+            let path = r.test_case.path.display().to_string();
+            let before = *baseline.get(&path)?;
+            let after = r.duration?.as_secs_f64() * 1000.0;
+            let slowdown = (after - before) / before.max(f64::EPSILON) * 100.0;
+            (slowdown > threshold).then_some((path, before, after, slowdown))
+        })
+        .collect();
+    regressions.sort_by(|(_, _, _, a), (_, _, _, b)| b.total_cmp(a));
+
+    if regressions.is_empty() {
+        println!(
+            "No tests are more than {}% slower than the baseline",
+            threshold
+        );
+        return Ok(false);
+    }
+
+    println!(
+        "{} tests are more than {}% slower than the baseline:",
+        regressions.len(),
+        threshold
+    );
+    let latency = |ms: f64| args.latency(Duration::from_secs_f64(ms / 1000.0));
+    for (path, before, after, slowdown) in &regressions {
+        println!(
+            "  - {}: {} -> {} ({})",
+            path,
+            latency(*before),
+            latency(*after).color(args.actual_color),
+            format!("+{:.0}%", slowdown).color(args.failure_color)
+        );
+    }
+    Ok(true)
+}
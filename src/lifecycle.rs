@@ -0,0 +1,140 @@
+//! The requests sent around each tested `/move`: `/start` and `/end` with
+//! `--send-lifecycle`, a test's `lead_in` turns, and the per-endpoint latency
+//! they are reported with.
+
+use std::{
+    collections::BTreeMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+use crate::client::SnakeClient;
+use crate::info::base_url;
+use crate::{Args, TestCase};
+
+const ENDPOINTS: [&str; 3] = ["start", "move", "end"];
+
+#[derive(Default)]
+pub struct Lifecycle {
+    enabled: bool,
+    /// Behind a lock as tests run at the same time with `--jobs`
+    latencies: Mutex<BTreeMap<&'static str, Vec<Duration>>>,
+}
+
+impl Lifecycle {
+    pub fn new(enabled: bool) -> Self {
+        Lifecycle {
+            enabled,
+            ..Default::default()
+        }
+    }
+
+    /// Runs the move request, wrapped in `/start` and `/end` when enabled, returning how long the move took
+    ///
+    /// `request_move` returns how long its move request took, so what it does around it isn't
+    /// counted. When it fails, the whole time it took is returned instead.
+    ///
+    /// A test with `lead_in` turns or `steps` always gets a `/start` and an `/end`, with a `/move`
+    /// for each lead-in state whose response is only checked for errors.
+    pub fn run<T>(
+        &self,
+        client: &SnakeClient,
+        move_url: &str,
+        test_case: &TestCase,
+        request_move: impl FnOnce() -> Result<(T, Duration)>,
+    ) -> (Result<T>, Duration) {
+        let state = &test_case.state;
+
+        let started = self.enabled || !test_case.lead_in.is_empty() || !test_case.steps.is_empty();
+        if started {
+            let first_state = test_case
+                .lead_in
+                .first()
+                .or_else(|| test_case.steps.first().map(|step| &step.state))
+                .unwrap_or(state);
+            if let Err(e) = self.send(client, move_url, "start", first_state) {
+                return (Err(e), Duration::default());
+            }
+        }
+        // Every game that got a `/start` gets its `/end`, even when a request in between failed,
+        // so the server doesn't keep it open. The first error is the one reported.
+        let end = |result: Result<T>| {
+            let ended = self.send(client, move_url, "end", state);
+            result.and_then(|r| ended.map(|()| r))
+        };
+        for (i, lead_in_state) in test_case.lead_in.iter().enumerate() {
+            if let Err(e) = client
+                .request_move_uncached(move_url, lead_in_state, &test_case.headers)
+                .with_context(|| format!("Lead-in turn {} failed", i + 1))
+            {
+                return (end(Err(e)), Duration::default());
+            }
+        }
+
+        let start = Instant::now();
+        let (result, duration) = match request_move() {
+            Ok((result, duration)) => (Ok(result), duration),
+            Err(e) => (Err(e), start.elapsed()),
+        };
+
+        if self.enabled {
+            self.record("move", duration);
+        }
+        if !started {
+            return (result, duration);
+        }
+        (end(result), duration)
+    }
+
+    fn send(
+        &self,
+        client: &SnakeClient,
+        move_url: &str,
+        endpoint: &'static str,
+        state: &Value,
+    ) -> Result<()> {
+        let url = format!("{}/{}", base_url(move_url), endpoint);
+        let start = Instant::now();
+        client
+            .post(&url, state)
+            .with_context(|| format!("The /{} request failed", endpoint))?;
+        self.record(endpoint, start.elapsed());
+        Ok(())
+    }
+
+    fn record(&self, endpoint: &'static str, latency: Duration) {
+        self.latencies
+            .lock()
+            .unwrap()
+            .entry(endpoint)
+            .or_default()
+            .push(latency);
+    }
+
+    /// Prints min/mean/max for every endpoint, nothing when lifecycle requests are disabled
+    pub fn print_latencies(&self, args: &Args) {
+        if !self.enabled {
+            return;
+        }
+
+        let latencies = self.latencies.lock().unwrap();
+        for endpoint in ENDPOINTS {
+            let durations = match latencies.get(endpoint) {
+                Some(durations) if !durations.is_empty() => durations,
+                _ => continue,
+            };
+            let total: Duration = durations.iter().sum();
+            println!(
+                "/{:<5} min {}, mean {}, max {} over {} requests",
+                endpoint,
+                args.latency(*durations.iter().min().unwrap()),
+                args.latency(total / durations.len() as u32),
+                args.latency(*durations.iter().max().unwrap()),
+                durations.len()
+            );
+        }
+    }
+}
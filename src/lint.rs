@@ -0,0 +1,162 @@
+//! Offline checks for test files, run with `--lint`.
+//!
+//! These catch authoring mistakes (states the engine could never produce)
+//! without sending anything to a server.
+
+use std::collections::HashSet;
+
+use colored::Colorize;
+
+use crate::board::{Coord, Direction, GameState};
+use crate::{Args, TestCase};
+
+/// Returns a human readable message for every problem found in the test case
+pub fn lint_test_case(args: &Args, test_case: &TestCase) -> Vec<String> {
+    let mut issues = vec![];
+
+    if args.require_description
+        && test_case
+            .description
+            .as_deref()
+            .is_none_or(|d| d.trim().is_empty())
+    {
+        issues.push("Has no description, which --require-description requires".to_owned());
+    }
+
+    match GameState::from_value(&test_case.state) {
+        Ok(state) => {
+            issues.extend(check_bounds(&state));
+            issues.extend(check_hazards(
+                &state,
+                test_case.ruleset(),
+                test_case.state["game"]["map"].as_str(),
+            ));
+            issues.extend(check_expected_moves(args, &state, &test_case.expected));
+        }
+        Err(e) => issues.push(format!("State could not be parsed: {}", e)),
+    }
+
+    issues
+}
+
+fn check_bounds(state: &GameState) -> Vec<String> {
+    let board = &state.board;
+    let mut issues = vec![];
+
+    let mut check = |entity: String, c: &Coord| {
+        if !board.contains(*c) {
+            issues.push(format!(
+                "{} at ({}, {}) is outside the {}x{} board",
+                entity, c.x, c.y, board.width, board.height
+            ));
+        }
+    };
+
+    for f in &board.food {
+        check("Food".to_owned(), f);
+    }
+    for h in &board.hazards {
+        check("Hazard".to_owned(), h);
+    }
+    for snake in &board.snakes {
+        for (i, segment) in snake.body.iter().enumerate() {
+            check(
+                format!("Snake \"{}\" ({}) body segment {}", snake.name, snake.id, i),
+                segment,
+            );
+        }
+    }
+
+    issues
+}
+
+/// Hazards that the ruleset the state claims to be from could never have placed
+///
+/// Only royale and hazard maps place hazards. Royale shrinks the board from its
+/// edges, so the cells without hazards are always a rectangle, and never empty.
+fn check_hazards(state: &GameState, ruleset: Option<&str>, map: Option<&str>) -> Vec<String> {
+    let board = &state.board;
+    let hazards: HashSet<_> = board.hazards.iter().copied().collect();
+    let cells = board.width * board.height;
+    let from_map = map.is_some_and(|m| m != "standard" && m != "empty");
+
+    match ruleset {
+        Some("royale") => {
+            let safe: Vec<_> = (0..board.width)
+                .flat_map(|x| (0..board.height).map(move |y| Coord { x, y }))
+                .filter(|c| !hazards.contains(c))
+                .collect();
+            if safe.is_empty() {
+                return vec![format!(
+                    "Hazards cover the whole {}x{} board, royale always leaves an area without them",
+                    board.width, board.height
+                )];
+            }
+            let (min_x, max_x) = (
+                safe.iter().map(|c| c.x).min().unwrap(),
+                safe.iter().map(|c| c.x).max().unwrap(),
+            );
+            let (min_y, max_y) = (
+                safe.iter().map(|c| c.y).min().unwrap(),
+                safe.iter().map(|c| c.y).max().unwrap(),
+            );
+            let area = (max_x - min_x + 1) * (max_y - min_y + 1);
+            if !from_map && safe.len() as i32 != area {
+                return vec![
+                    "The cells without hazards don't form a rectangle, which royale's shrinking always leaves"
+                        .to_owned(),
+                ];
+            }
+            vec![]
+        }
+        Some(name) if !hazards.is_empty() && !from_map => vec![format!(
+            "Has {} hazards, but the {} ruleset doesn't place any",
+            hazards.len(),
+            name
+        )],
+        _ => {
+            if !hazards.is_empty() && hazards.len() as i32 >= cells {
+                return vec![format!(
+                    "Hazards cover the whole {}x{} board",
+                    board.width, board.height
+                )];
+            }
+            vec![]
+        }
+    }
+}
+
+/// Expected moves that kill the snake, each with the board and the cell it would move into highlighted
+fn check_expected_moves(args: &Args, state: &GameState, expected: &[String]) -> Vec<String> {
+    expected
+        .iter()
+        .filter_map(|m| {
+            let direction: Direction = m.parse().ok()?;
+            let cause = state.fatal_move(direction)?;
+            let target = state.geometry().step(state.you.head, direction);
+            let board = state.render_with_target(target, |cell| highlight(args, cell));
+            let indented: Vec<_> = board.lines().map(|l| format!("    {}", l)).collect();
+            Some(format!(
+                "Expects \"{}\", which moves the head into {} at {}:\n{}",
+                m,
+                cause,
+                target,
+                indented.join("\n")
+            ))
+        })
+        .collect()
+}
+
+/// The highlighted cell of a rendered board, in `--failure-color`
+///
+/// Without colors the target has to stand out some other way, so it's drawn as `X`.
+pub fn highlight(args: &Args, cell: char) -> String {
+    if colored::control::SHOULD_COLORIZE.should_colorize() {
+        cell.to_string()
+            .color(args.failure_color)
+            .bold()
+            .to_string()
+    } else {
+        "X".to_owned()
+    }
+}
@@ -0,0 +1,51 @@
+//! A saved record of the move the server made in each test, so a later run can fail on any
+//! test where it moves differently, even tests that don't say which move is right yet.
+
+use std::{
+    collections::BTreeMap,
+    fs::{read_to_string, write},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+
+use crate::{TestFailure, TestRun};
+
+/// The move per test path
+pub type Baseline = BTreeMap<String, String>;
+
+pub fn save(path: &Path, results: &[TestRun]) -> Result<()> {
+    let baseline: Baseline = results
+        .iter()
+        .filter_map(|r| {
+            Some((
+                r.test_case.path.display().to_string(),
+                r.actual_move.clone()?,
+            ))
+        })
+        .collect();
+    write(path, serde_json::to_string_pretty(&baseline)? + "\n")?;
+    Ok(())
+}
+
+pub fn load(path: &Path) -> Result<Baseline> {
+    serde_json::from_str(&read_to_string(path)?)
+        .with_context(|| format!("Could not read the move baseline {}", path.display()))
+}
+
+/// Fails a test that otherwise passed when its move isn't the one in the baseline
+///
+/// Tests that aren't in the baseline, or that already failed, are left as they are.
+pub fn check(baseline: &Baseline, mut test_run: TestRun) -> TestRun {
+    let before = baseline.get(&test_run.test_case.path.display().to_string());
+    if let (Ok(()), Some(before), Some(actual)) = (&test_run.result, before, &test_run.actual_move)
+    {
+        if before != actual {
+            test_run.result = Err(TestFailure::ChangedFromBaseline(
+                before.clone(),
+                actual.clone(),
+            ));
+        }
+    }
+    test_run
+}
@@ -0,0 +1,28 @@
+//! Desktop notifications for `--notify`.
+
+use log::debug;
+use notify_rust::Notification;
+
+use crate::RunOutcome;
+
+/// Sends a desktop notification with the result of a run
+///
+/// Where notifications aren't supported, ie without a notification daemon, nothing is shown and the run carries on.
+pub fn notify_outcome(outcome: &RunOutcome) {
+    let body = if outcome.succeeded {
+        format!("All {} tests passed", outcome.total)
+    } else {
+        format!(
+            "{} of {} tests failed",
+            outcome.total - outcome.passed,
+            outcome.total
+        )
+    };
+    if let Err(e) = Notification::new()
+        .summary("battlesnake_tests")
+        .body(&body)
+        .show()
+    {
+        debug!("Could not show a notification: {}", e);
+    }
+}
@@ -0,0 +1,51 @@
+//! Running requests concurrently, capped by `--jobs`.
+
+use std::thread;
+
+/// Applies `f` to every item with at most `jobs` running at the same time, keeping the order of the items
+pub fn map_bounded<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let f = &f;
+    let mut results = Vec::with_capacity(items.len());
+
+    for chunk in items.chunks(jobs.max(1)) {
+        thread::scope(|scope| {
+            let handles: Vec<_> = chunk
+                .iter()
+                .map(|item| scope.spawn(move || f(item)))
+                .collect();
+            results.extend(handles.into_iter().map(|h| h.join().unwrap()));
+        });
+    }
+
+    results
+}
+
+/// Like `map_bounded`, for items that `f` takes ownership of
+pub fn map_owned<T, R, F>(items: Vec<T>, jobs: usize, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+{
+    let f = &f;
+    let mut results = Vec::with_capacity(items.len());
+    let mut items = items.into_iter().peekable();
+
+    while items.peek().is_some() {
+        let chunk: Vec<_> = items.by_ref().take(jobs.max(1)).collect();
+        thread::scope(|scope| {
+            let handles: Vec<_> = chunk
+                .into_iter()
+                .map(|item| scope.spawn(move || f(item)))
+                .collect();
+            results.extend(handles.into_iter().map(|h| h.join().unwrap()));
+        });
+    }
+
+    results
+}
@@ -0,0 +1,56 @@
+//! `--profile`, a summary of how long the server took to answer across the run, for catching a
+//! search that got slower before it starts missing the engine's deadline.
+
+use std::{fs::write, path::Path, time::Duration};
+
+use anyhow::{Context, Result};
+
+use crate::{print_slowest, Args, TestRun};
+
+/// How many of the slowest tests are listed when `--slowest` isn't given
+const DEFAULT_SLOWEST: usize = 5;
+
+/// The min, mean, p95 and max of the move requests followed by the slowest tests
+pub fn print_profile(args: &Args, results: &[TestRun]) {
+    let mut durations: Vec<Duration> = results.iter().filter_map(|r| r.duration).collect();
+    if durations.is_empty() {
+        println!("Latency profile: no requests were answered");
+        return;
+    }
+    durations.sort();
+
+    let mean = durations.iter().sum::<Duration>() / durations.len() as u32;
+    println!(
+        "Latency profile over {} requests: min {}, mean {}, p95 {}, max {}",
+        durations.len(),
+        args.latency(durations[0]),
+        args.latency(mean),
+        args.latency(percentile(&durations, 95)),
+        args.latency(durations[durations.len() - 1])
+    );
+    print_slowest(args, results, args.slowest.unwrap_or(DEFAULT_SLOWEST));
+}
+
+/// The nearest-rank percentile of durations that are already sorted
+fn percentile(sorted: &[Duration], p: usize) -> Duration {
+    let rank = (sorted.len() * p).div_ceil(100);
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// One row per test with its outcome and how long its move request took in milliseconds,
+/// empty for the tests that never got an answer
+pub fn write_csv(path: &Path, results: &[TestRun]) -> Result<()> {
+    let mut csv = "path,outcome,latency_ms\n".to_owned();
+    for r in results {
+        let outcome = match &r.result {
+            Ok(()) => "passed",
+            Err(failure) => failure.outcome(),
+        };
+        let latency = r
+            .duration
+            .map(|d| format!("{:.3}", d.as_secs_f64() * 1000.0))
+            .unwrap_or_default();
+        csv += &format!("{},{},{}\n", r.test_case.path.display(), outcome, latency);
+    }
+    write(path, csv).with_context(|| format!("Could not write the profile {}", path.display()))
+}
@@ -0,0 +1,87 @@
+//! The progress bar drawn on stderr while the suite runs, so a big suite isn't silent until the
+//! report.
+
+use std::{
+    io::{self, IsTerminal, Write},
+    time::Instant,
+};
+
+use colored::Colorize;
+
+use crate::{Args, TestRun};
+
+const WIDTH: usize = 30;
+
+pub struct Progress {
+    total: usize,
+    passed: usize,
+    failed: usize,
+    started: Instant,
+    /// Only drawn when stderr is a terminal, so logs and CI output stay clean
+    enabled: bool,
+}
+
+impl Progress {
+    pub fn new(args: &Args, total: usize) -> Self {
+        let progress = Progress {
+            total,
+            passed: 0,
+            failed: 0,
+            started: Instant::now(),
+            enabled: !args.no_progress && total > 0 && io::stderr().is_terminal(),
+        };
+        progress.draw();
+        progress
+    }
+
+    /// Erases the bar, before anything else is printed
+    pub fn clear(&self) {
+        if self.enabled {
+            eprint!("\r\x1b[2K");
+        }
+    }
+
+    /// Counts a finished test and draws the bar again
+    pub fn advance(&mut self, test_run: &TestRun) {
+        if test_run.result.is_ok() {
+            self.passed += 1;
+        } else {
+            self.failed += 1;
+        }
+        self.draw();
+    }
+
+    fn draw(&self) {
+        if !self.enabled {
+            return;
+        }
+
+        let cells = |n: usize| (n * WIDTH).checked_div(self.total).unwrap_or(0);
+        let passed = cells(self.passed);
+        let failed = cells(self.passed + self.failed) - passed;
+        let bar = if colored::control::SHOULD_COLORIZE.should_colorize() {
+            format!(
+                "{}{}{}",
+                "█".repeat(passed).green(),
+                "█".repeat(failed).red(),
+                " ".repeat(WIDTH - passed - failed)
+            )
+        } else {
+            format!(
+                "{}{}{}",
+                "#".repeat(passed),
+                "F".repeat(failed),
+                "-".repeat(WIDTH - passed - failed)
+            )
+        };
+        eprint!(
+            "\r\x1b[2K[{}] {}/{} tests, {} failed, {:.0}s",
+            bar,
+            self.passed + self.failed,
+            self.total,
+            self.failed,
+            self.started.elapsed().as_secs_f64()
+        );
+        let _ = io::stderr().flush();
+    }
+}
@@ -0,0 +1,34 @@
+//! Move scores reported by the server, checked against a test's `expected_ranking`.
+
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+
+/// The best `k` moves from the scores at `path` in the response, highest score first
+///
+/// The path is a `.` separated list of keys, ie `debug.scores`, pointing at an
+/// object mapping each move to its score.
+pub fn top_moves(response: &Value, path: &str, k: usize) -> Result<Vec<String>> {
+    let scores = path
+        .split('.')
+        .try_fold(response, |value, key| value.get(key))
+        .and_then(Value::as_object)
+        .ok_or_else(|| {
+            anyhow!(
+                "The response has no \"{}\" object of move scores to check expected_ranking against",
+                path
+            )
+        })?;
+
+    let mut scored = scores
+        .iter()
+        .map(|(m, score)| {
+            score
+                .as_f64()
+                .map(|score| (m.clone(), score))
+                .ok_or_else(|| anyhow!("The score for \"{}\" is not a number: {}", m, score))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    scored.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    Ok(scored.into_iter().take(k).map(|(m, _)| m).collect())
+}
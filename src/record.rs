@@ -0,0 +1,133 @@
+//! Updating the `expected` moves of test files from the server's responses.
+
+use std::io::{self, BufRead, Write};
+
+use anyhow::Result;
+use colored::*;
+
+use crate::board::GameState;
+use crate::format;
+use crate::stale;
+use crate::{Args, TestFailure, TestRun};
+
+/// The server's move, when the test doesn't accept it
+///
+/// Tests that went wrong on an earlier one of their `steps` are left alone, as only the
+/// last step's expected moves are recorded.
+fn new_move(run: &TestRun) -> Option<&String> {
+    if let Err(TestFailure::IncorrectStep(step, steps, _, _)) = &run.result {
+        if step < steps {
+            return None;
+        }
+    }
+    run.actual_move
+        .as_ref()
+        .filter(|actual| !run.test_case.accepts(actual))
+}
+
+/// Asks, for every test where the server disagreed with `expected`, whether the
+/// server's move should become the new expected move
+pub fn record_interactive(args: &Args, results: &[TestRun]) -> Result<()> {
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    let mut updated = 0;
+
+    for run in results {
+        let actual = match new_move(run) {
+            Some(actual) => actual,
+            None => continue,
+        };
+
+        println!("{}", run.test_case.path.to_str().unwrap());
+        if let Some(description) = &run.test_case.description {
+            println!("Description: {}", description);
+        }
+        if let Ok(state) = GameState::from_value(&run.test_case.state) {
+            println!("\n{}", state.render());
+        }
+        println!(
+            "Expected: [{}]\nServer moved: {}",
+            run.test_case.expected.join(", ").color(args.expected_color),
+            actual.color(args.actual_color)
+        );
+
+        loop {
+            print!("Accept the new move? [y]es, [n]o, [q]uit: ");
+            io::stdout().flush()?;
+
+            let answer = match lines.next() {
+                Some(line) => line?,
+                None => "q".to_owned(),
+            };
+            match answer.trim() {
+                "y" => {
+                    let mut test_case_file = format::read_test_case_file(&run.test_case.path)?;
+                    if let Some(last) = test_case_file.steps.last_mut() {
+                        last.expected = vec![actual.clone()];
+                    } else if test_case_file.expected_exact.is_some() {
+                        test_case_file.expected_exact = Some(actual.clone());
+                    } else {
+                        test_case_file.expected = vec![actual.clone().into()];
+                    }
+                    // A recorded move replaces any strategy the expectation was computed with
+                    test_case_file.expected_strategy = None;
+                    test_case_file.state_hash =
+                        Some(stale::state_hash(&stale::hashed_state(&test_case_file)));
+                    format::write_test_case_file(&run.test_case.path, &test_case_file)?;
+                    updated += 1;
+                    break;
+                }
+                "n" => break,
+                "q" => {
+                    println!("\nUpdated {} test files", updated);
+                    return Ok(());
+                }
+                _ => continue,
+            }
+        }
+        println!();
+    }
+
+    println!("Updated {} test files", updated);
+
+    Ok(())
+}
+
+/// Prints what `--record-interactive` would change were every new move accepted, without writing
+pub fn print_expected_diff(args: &Args, results: &[TestRun]) {
+    let mut changed = 0;
+    for run in results {
+        let actual = match new_move(run) {
+            Some(actual) => actual,
+            None => continue,
+        };
+        changed += 1;
+
+        let old: Vec<_> = run
+            .test_case
+            .expected
+            .iter()
+            .map(|e| format!("\"{}\"", e))
+            .collect();
+        let (old, new) = if run.test_case.exact {
+            (
+                format!("- \"expected_exact\": {}", old.join(", ")),
+                format!("+ \"expected_exact\": \"{}\"", actual),
+            )
+        } else {
+            (
+                format!("- \"expected\": [{}]", old.join(", ")),
+                format!("+ \"expected\": [\"{}\"]", actual),
+            )
+        };
+        println!("{}", run.test_case.path.to_str().unwrap());
+        println!("{}", old.color(args.expected_color));
+        println!("{}\n", new.color(args.actual_color));
+    }
+
+    println!(
+        "{} of {} test files would change, none were modified",
+        changed,
+        results.len()
+    );
+}
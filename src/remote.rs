@@ -0,0 +1,185 @@
+//! `--dir` given as a URL, a shared suite of test cases in a git repository or an archive that
+//! is fetched once into a local cache and run from there.
+
+use std::{
+    env,
+    fs::{create_dir_all, read, read_dir, remove_dir_all, rename, write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use anyhow::{anyhow, Context, Result};
+use log::{debug, info};
+use sha2::{Digest, Sha256};
+
+use crate::{config_dump::redact_url, Args};
+
+/// The archives that are extracted, by the end of their URL, with the `tar` or `unzip` arguments
+const ARCHIVES: [(&str, &[&str]); 4] = [
+    (".tar.gz", &["tar", "-xzf"]),
+    (".tgz", &["tar", "-xzf"]),
+    (".tar", &["tar", "-xf"]),
+    (".zip", &["unzip", "-q"]),
+];
+
+/// Replaces a `--dir` that is a URL with the local copy of the suite, fetching it when it isn't
+/// cached yet or `--refresh-suite` is given
+pub fn resolve(args: &mut Args) -> Result<()> {
+    if !is_remote(&args.test_directory) {
+        return Ok(());
+    }
+    let url = args.test_directory.clone();
+    let dir = fetch(&url, &cache_dir(args)?, args.refresh_suite)
+        .with_context(|| format!("Could not fetch the test suite {}", redact_url(&url)))?;
+    args.test_directory = dir.to_str().map(str::to_owned).ok_or_else(|| {
+        anyhow!(
+            "The suite cache directory {} is not valid UTF-8",
+            dir.display()
+        )
+    })?;
+    Ok(())
+}
+
+/// Whether `--dir` is a URL to fetch rather than a local directory
+pub fn is_remote(dir: &str) -> bool {
+    ["http://", "https://", "git://", "ssh://", "file://", "git@"]
+        .iter()
+        .any(|prefix| dir.starts_with(prefix))
+}
+
+/// `--suite-cache`, or `battlesnake_tests/suites` in the user's cache directory
+fn cache_dir(args: &Args) -> Result<PathBuf> {
+    if let Some(dir) = &args.suite_cache {
+        return Ok(dir.clone());
+    }
+    let cache = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+        .ok_or_else(|| anyhow!("Neither XDG_CACHE_HOME nor HOME is set, pass --suite-cache"))?;
+    Ok(cache.join("battlesnake_tests").join("suites"))
+}
+
+/// The suite's directory in the cache, named after the URL so it can be found by hand
+fn suite_dir(cache: &Path, url: &str) -> PathBuf {
+    let hash: String = Sha256::digest(url.as_bytes())
+        .iter()
+        .take(6)
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let name: String = url
+        .split("://")
+        .last()
+        .unwrap_or(url)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .take(60)
+        .collect();
+    cache.join(format!("{}-{}", name.trim_matches('-'), hash))
+}
+
+/// The local directory of the suite at `url`
+///
+/// A git repository is pulled again on a refresh, an archive downloaded again. A fetch goes into
+/// a temporary directory first, so one that fails halfway doesn't leave a broken suite cached.
+fn fetch(url: &str, cache: &Path, refresh: bool) -> Result<PathBuf> {
+    let dir = suite_dir(cache, url);
+    let archive = ARCHIVES
+        .iter()
+        .find(|(ext, _)| url.split(['?', '#']).next().unwrap_or(url).ends_with(ext));
+
+    if dir.is_dir() && !refresh {
+        debug!("Using the cached suite {}", dir.display());
+        return Ok(root(&dir));
+    }
+    if dir.is_dir() && archive.is_none() {
+        info!("Updating {}", redact_url(url));
+        run(Command::new("git")
+            .args(["-C"])
+            .arg(&dir)
+            .args(["pull", "-q", "--ff-only"]))?;
+        return Ok(root(&dir));
+    }
+
+    create_dir_all(cache)
+        .with_context(|| format!("Could not create the suite cache {}", cache.display()))?;
+    let partial = dir.with_extension("partial");
+    if partial.exists() {
+        remove_dir_all(&partial)?;
+    }
+    info!("Fetching {}", redact_url(url));
+    match archive {
+        Some((ext, command)) => download(url, ext, command, &partial)?,
+        None => clone(url, &partial)?,
+    }
+    if dir.exists() {
+        remove_dir_all(&dir)?;
+    }
+    rename(&partial, &dir)?;
+    Ok(root(&dir))
+}
+
+/// Clones the repository without its history, a `#branch` or `#tag` at the end choosing what
+/// is checked out
+fn clone(url: &str, into: &Path) -> Result<()> {
+    let mut command = Command::new("git");
+    command.args(["clone", "-q", "--depth", "1"]);
+    let url = match url.rsplit_once('#') {
+        Some((url, reference)) => {
+            command.args(["--branch", reference]);
+            url
+        }
+        None => url,
+    };
+    run(command.arg(url).arg(into))
+}
+
+/// Downloads the archive and extracts it, reading a `file://` archive from the disk instead
+fn download(url: &str, ext: &str, command: &[&str], into: &Path) -> Result<()> {
+    let body = match url.strip_prefix("file://") {
+        Some(path) => read(path).with_context(|| format!("Could not read {}", path))?,
+        None => {
+            debug!("GET {}", redact_url(url));
+            reqwest::blocking::get(url)?
+                .error_for_status()?
+                .bytes()?
+                .to_vec()
+        }
+    };
+    create_dir_all(into)?;
+    let archive = into.join(format!("suite{}", ext));
+    write(&archive, &body)?;
+    run(Command::new(command[0])
+        .args(&command[1..])
+        .arg(&archive)
+        .current_dir(into))?;
+    std::fs::remove_file(&archive)?;
+    Ok(())
+}
+
+fn run(command: &mut Command) -> Result<()> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let output = command
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("Could not run {}, is it installed?", program))?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// The single directory an archive usually wraps its files in, or the suite's directory itself
+fn root(dir: &Path) -> PathBuf {
+    let entries: Vec<_> = match read_dir(dir) {
+        Ok(entries) => entries.filter_map(|e| e.ok()).map(|e| e.path()).collect(),
+        Err(_) => return dir.to_path_buf(),
+    };
+    match entries.as_slice() {
+        [only] if only.is_dir() => only.clone(),
+        _ => dir.to_path_buf(),
+    }
+}
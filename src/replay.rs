@@ -0,0 +1,82 @@
+//! The `replay` subcommand, which asks the server what it would have done at every
+//! turn of a saved game.
+//!
+//! Nothing is asserted, it prints the server's move next to the move the snake
+//! actually made in the game so the two can be compared.
+
+use std::{fs::read_to_string, path::Path};
+
+use anyhow::{anyhow, Result};
+use colored::*;
+
+use crate::board::{Coord, Direction};
+use crate::client::SnakeClient;
+use crate::engine::EngineExport;
+use crate::Args;
+
+pub fn replay(
+    args: &Args,
+    url: &str,
+    export: &Path,
+    snake: &str,
+    from: Option<u32>,
+    to: Option<u32>,
+) -> Result<()> {
+    let export: EngineExport = serde_json::from_str(&read_to_string(export)?)?;
+    let you_id = export
+        .find_snake_id(snake)
+        .ok_or_else(|| anyhow!("No snake with the id or name \"{}\" in this game", snake))?;
+
+    let client = SnakeClient::new(args)?;
+    let mut agreed = 0;
+    let mut total = 0;
+
+    println!("{:>5}  {:>10}  {:>10}", "Turn", "Historical", "Server");
+
+    for (i, frame) in export.frames.iter().enumerate() {
+        if from.is_some_and(|from| frame.turn < from) || to.is_some_and(|to| frame.turn > to) {
+            continue;
+        }
+        let state = match frame.to_state(&export.game, &you_id) {
+            Some(state) => state,
+            None => break,
+        };
+
+        let historical = export.frames.get(i + 1).and_then(|next| {
+            let before: Coord = frame.snake(&you_id)?.body.first().copied()?.into();
+            let after: Coord = next.snake(&you_id)?.body.first().copied()?.into();
+            Direction::ALL
+                .iter()
+                .find(|d| state.geometry().step(before, **d) == after)
+                .map(|d| d.to_string())
+        });
+
+        let server = match client.request_move(url, &state.to_value()?) {
+            Ok(response) => response.r#move,
+            Err(e) => format!("error: {}", e),
+        };
+
+        let server_cell = match &historical {
+            Some(historical) => {
+                total += 1;
+                if server == *historical {
+                    agreed += 1;
+                    server.color(args.expected_color)
+                } else {
+                    server.color(args.actual_color)
+                }
+            }
+            None => server.normal(),
+        };
+        let historical = historical.unwrap_or_else(|| "-".to_owned());
+
+        println!("{:>5}  {:>10}  {:>10}", frame.turn, historical, server_cell);
+    }
+
+    println!(
+        "\nThe server agreed with the historical move on {} out of {} turns",
+        agreed, total
+    );
+
+    Ok(())
+}
@@ -0,0 +1,116 @@
+//! A copy-pasteable command that reruns a single failing test with the options
+//! that affect how it is run.
+
+use crate::client::RequestMethod;
+use crate::symmetry;
+use crate::{Args, TestCase};
+
+pub fn repro_command(args: &Args, test_case: &TestCase) -> String {
+    let mut parts = vec!["battlesnake_tests".to_owned()];
+    let mut option = |name: &str, value: Option<&str>| {
+        parts.push(name.to_owned());
+        if let Some(value) = value {
+            parts.push(quote(value));
+        }
+    };
+
+    for url in &args.url {
+        option("--url", Some(url));
+    }
+    if let Some(path) = &args.headers_file {
+        option("--headers-file", Some(&path.display().to_string()));
+    }
+    for (name, _) in &args.header {
+        option("--header", Some(&format!("{}: <redacted>", name)));
+    }
+    for (key, value) in &args.inject_field {
+        option("--inject-field", Some(&format!("{}={}", key, value)));
+    }
+    if let Some(key) = &args.wrap_key {
+        option("--wrap-key", Some(key));
+    }
+    for (key, value) in &args.add_field {
+        option("--add-field", Some(&format!("{}={}", key, value)));
+    }
+    if args.move_key != "move" {
+        option("--move-key", Some(&args.move_key));
+    }
+    if args.method == RequestMethod::Get {
+        option("--method", Some("GET"));
+        if args.query_key != "state" {
+            option("--query-key", Some(&args.query_key));
+        }
+    }
+    if args.content_type != "application/json" {
+        option("--content-type", Some(&args.content_type));
+    }
+    if let Some(ms) = args.timeout_ms {
+        option("--timeout-ms", Some(&ms.to_string()));
+    }
+    if let Some(ms) = args.connect_timeout {
+        option("--connect-timeout", Some(&ms.to_string()));
+    }
+    if args.retries > 0 {
+        option("--retries", Some(&args.retries.to_string()));
+        option(
+            "--retry-backoff-ms",
+            Some(&args.retry_backoff_ms.to_string()),
+        );
+    }
+    if let Some(version) = args.api_version {
+        option("--api-version", Some(version.as_str()));
+    }
+    if let Some(url) = &args.oracle_url {
+        option("--oracle-url", Some(url));
+    }
+    if args.send_lifecycle {
+        option("--send-lifecycle", None);
+    }
+    if args.check_idempotent {
+        option("--check-idempotent", None);
+    }
+    if args.require_shout {
+        option("--require-shout", None);
+    }
+    if args.check_legal {
+        option("--check-legal", None);
+    }
+    if let Some(ms) = args.max_latency_ms {
+        option("--max-latency-ms", Some(&ms.to_string()));
+    }
+    if args.repeat > 1 {
+        option("--repeat", Some(&args.repeat.to_string()));
+        if args.fail_on_flaky {
+            option("--fail-on-flaky", None);
+        }
+    }
+    if let Some(path) = &args.check_baseline {
+        option("--check-baseline", Some(&path.display().to_string()));
+    }
+    if args.self_play {
+        option("--self-play", None);
+        option("--max-turns", Some(&args.max_turns.to_string()));
+    }
+    if args.strict {
+        option("--strict", None);
+    }
+
+    if test_case.symmetry.is_some() {
+        option("--symmetry", None);
+    }
+
+    parts.push(quote(
+        &symmetry::source_path(test_case).display().to_string(),
+    ));
+    parts.join(" ")
+}
+
+/// Single quotes the value for a POSIX shell, unless it only has characters that don't need it
+fn quote(value: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "-_./:=@%+,".contains(c);
+    if !value.is_empty() && value.chars().all(safe) {
+        value.to_owned()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
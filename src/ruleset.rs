@@ -0,0 +1,119 @@
+//! `ruleset`, the game mode a test is written for, checked against its states so a test meant
+//! for `wrapped` or `royale` can't quietly run with a state from another mode.
+
+use serde_json::Value;
+
+use crate::TestCaseFile;
+
+/// The rulesets the engine ships, a declared ruleset has to be one of them
+pub const KNOWN: [&str; 5] = ["standard", "solo", "wrapped", "royale", "constrictor"];
+
+/// Sets `game.ruleset.name` of the states that don't have one to the declared ruleset, then
+/// lists every state that disagrees with it or couldn't happen under its rules
+///
+/// States without a declared ruleset are only checked against the rules of the one they name.
+pub fn apply(test_case_file: &mut TestCaseFile) -> Vec<String> {
+    let declared = test_case_file.ruleset.clone();
+    let mut problems = vec![];
+    if let Some(declared) = declared.as_deref().filter(|d| !KNOWN.contains(d)) {
+        problems.push(format!(
+            "ruleset: \"{}\" isn't one of {}",
+            declared,
+            KNOWN.join(", ")
+        ));
+        return problems;
+    }
+
+    let lead_in = test_case_file
+        .lead_in
+        .iter_mut()
+        .enumerate()
+        .map(|(i, state)| (format!("lead_in[{}]", i), state));
+    let steps = test_case_file
+        .steps
+        .iter_mut()
+        .enumerate()
+        .map(|(i, step)| (format!("steps[{}].state", i), &mut step.state));
+    let states = std::iter::once(("state".to_owned(), &mut test_case_file.state))
+        .filter(|(_, state)| !state.is_null())
+        .chain(steps)
+        .chain(lead_in);
+
+    for (path, state) in states {
+        if !state.is_object() {
+            // `validate` reports the state itself
+            continue;
+        }
+        let name = match (&state["game"]["ruleset"]["name"], &declared) {
+            (Value::String(name), _) => name.clone(),
+            (Value::Null, Some(declared)) if state["game"].is_object() => {
+                let game = &mut state["game"];
+                if !game["ruleset"].is_object() {
+                    game["ruleset"] = serde_json::json!({});
+                }
+                game["ruleset"]["name"] = declared.clone().into();
+                declared.clone()
+            }
+            _ => continue,
+        };
+        if let Some(declared) = declared.as_deref().filter(|d| *d != name) {
+            problems.push(format!(
+                "{}.game.ruleset.name: is \"{}\" but the test is for \"{}\"",
+                path, name, declared
+            ));
+            continue;
+        }
+        problems.extend(check_rules(&path, &name, state));
+    }
+    problems
+}
+
+/// What a state has to look like under the ruleset, beyond what every state needs
+fn check_rules(path: &str, name: &str, state: &Value) -> Vec<String> {
+    let snakes = state["board"]["snakes"]
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let no_hazards = state["board"]["hazards"]
+        .as_array()
+        .is_none_or(|h| h.is_empty());
+    let turn = state["turn"].as_u64().unwrap_or(0);
+    match name {
+        "royale" if no_hazards && turn >= shrink_every_n_turns(state) => vec![format!(
+            "{}.board.hazards: is empty on turn {}, but royale has hazards from turn {}",
+            path,
+            turn,
+            shrink_every_n_turns(state)
+        )],
+        "constrictor" => snakes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, snake)| {
+                let health = snake["health"].as_i64().filter(|h| *h != 100)?;
+                Some(format!(
+                    "{}.board.snakes[{}].health: is {}, but snakes always have 100 in constrictor",
+                    path, i, health
+                ))
+            })
+            .collect(),
+        "solo" if snakes.len() > 1 => vec![format!(
+            "{}.board.snakes: has {} snakes, but solo is played by one",
+            path,
+            snakes.len()
+        )],
+        _ => vec![],
+    }
+}
+
+/// The turn the first royale hazards appear on, which older engines sent as a string next to the
+/// ruleset's name and newer ones in its settings
+fn shrink_every_n_turns(state: &Value) -> u64 {
+    let ruleset = &state["game"]["ruleset"];
+    [
+        &ruleset["shrinkEveryNTurns"],
+        &ruleset["settings"]["royale"]["shrinkEveryNTurns"],
+    ]
+    .iter()
+    .find_map(|v| v.as_u64().or_else(|| v.as_str()?.parse().ok()))
+    .unwrap_or(25)
+}
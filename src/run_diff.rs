@@ -0,0 +1,128 @@
+//! `--save-run`, the outcome and move of every test of a run saved to a file, and the `diff`
+//! subcommand that compares two of them to show what a change to the snake broke or fixed.
+
+use std::{
+    collections::BTreeMap,
+    fs::{read_to_string, write},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use colored::{Color, Colorize};
+use serde::{Deserialize, Serialize};
+
+use crate::{Args, LoadError, TestRun};
+
+/// How a test did in a saved run
+#[derive(Serialize, Deserialize, PartialEq, Eq)]
+pub struct SavedTest {
+    /// `passed` or the kind of failure
+    pub outcome: String,
+    #[serde(rename = "move", skip_serializing_if = "Option::is_none")]
+    pub actual_move: Option<String>,
+}
+
+/// The tests of a run by their path
+pub type SavedRun = BTreeMap<String, SavedTest>;
+
+pub fn save(path: &Path, results: &[TestRun], load_errors: &[LoadError]) -> Result<()> {
+    let mut run: SavedRun = results
+        .iter()
+        .map(|r| {
+            let outcome = match &r.result {
+                Ok(()) => "passed",
+                Err(failure) => failure.outcome(),
+            };
+            (
+                r.test_case.path.display().to_string(),
+                SavedTest {
+                    outcome: outcome.to_owned(),
+                    actual_move: r.actual_move.clone(),
+                },
+            )
+        })
+        .collect();
+    for e in load_errors {
+        run.insert(
+            e.path.display().to_string(),
+            SavedTest {
+                outcome: "load_error".to_owned(),
+                actual_move: None,
+            },
+        );
+    }
+    write(path, serde_json::to_string_pretty(&run)? + "\n")
+        .with_context(|| format!("Could not write the run {}", path.display()))
+}
+
+pub fn load(path: &Path) -> Result<SavedRun> {
+    serde_json::from_str(
+        &read_to_string(path).with_context(|| format!("Could not read {}", path.display()))?,
+    )
+    .with_context(|| format!("Could not parse the saved run {}", path.display()))
+}
+
+/// Prints the tests whose outcome or move differs between the runs, returning false when any
+/// test that passed before fails after
+pub fn diff(args: &Args, before: &Path, after: &Path) -> Result<bool> {
+    let (before, after) = (load(before)?, load(after)?);
+
+    let mut newly_failing = vec![];
+    let mut newly_passing = vec![];
+    let mut changed_failure = vec![];
+    let mut changed_move = vec![];
+    for (path, now) in &after {
+        let then = match before.get(path) {
+            Some(then) => then,
+            None => continue,
+        };
+        let moves = format!(
+            "{} -> {}",
+            then.actual_move.as_deref().unwrap_or("none"),
+            now.actual_move.as_deref().unwrap_or("none")
+        );
+        match (then.outcome == "passed", now.outcome == "passed") {
+            (true, false) => newly_failing.push(format!("{} ({}, {})", path, now.outcome, moves)),
+            (false, true) => {
+                newly_passing.push(format!("{} (was {}, {})", path, then.outcome, moves))
+            }
+            _ if then.outcome != now.outcome => {
+                changed_failure.push(format!("{} ({} -> {})", path, then.outcome, now.outcome))
+            }
+            _ if then.actual_move != now.actual_move => {
+                changed_move.push(format!("{} ({})", path, moves))
+            }
+            _ => {}
+        }
+    }
+    let added: Vec<_> = after.keys().filter(|p| !before.contains_key(*p)).collect();
+    let removed: Vec<_> = before.keys().filter(|p| !after.contains_key(*p)).collect();
+
+    let section = |title: &str, color: Color, lines: &[String]| {
+        if !lines.is_empty() {
+            println!("{} ({}):", title.color(color), lines.len());
+            for line in lines {
+                println!("  - {}", line);
+            }
+            println!();
+        }
+    };
+    section("Newly failing", args.failure_color, &newly_failing);
+    section("Newly passing", args.expected_color, &newly_passing);
+    section("Failing differently", args.failure_color, &changed_failure);
+    section("Moved differently", args.actual_color, &changed_move);
+    let names = |paths: &[&String]| paths.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+    section("Only in the second run", args.actual_color, &names(&added));
+    section("Only in the first run", args.actual_color, &names(&removed));
+
+    println!(
+        "newly_failing={} newly_passing={} failing_differently={} moved_differently={} added={} removed={}",
+        newly_failing.len(),
+        newly_passing.len(),
+        changed_failure.len(),
+        changed_move.len(),
+        added.len(),
+        removed.len()
+    );
+    Ok(newly_failing.is_empty())
+}
@@ -0,0 +1,86 @@
+//! Scored `expected` moves, ie `{"move": "left", "score": 0.5}` for an acceptable move next to
+//! the best one, and the quality score of a run that `--min-score` checks.
+
+use std::collections::BTreeMap;
+
+use anyhow::{anyhow, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::TestRun;
+
+/// An entry of `expected`, a move on its own or with how good a move it is
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum ExpectedMove {
+    /// Scores 1, as good as any move
+    Move(String),
+    /// Scores from 0 for a move that only just passes up to 1 for the best move
+    Scored { r#move: String, score: f64 },
+}
+
+impl ExpectedMove {
+    pub fn name(&self) -> &str {
+        match self {
+            ExpectedMove::Move(m) | ExpectedMove::Scored { r#move: m, .. } => m,
+        }
+    }
+}
+
+impl From<String> for ExpectedMove {
+    fn from(m: String) -> Self {
+        ExpectedMove::Move(m)
+    }
+}
+
+/// The moves of the entries, without their scores
+pub fn moves(expected: &[ExpectedMove]) -> Vec<String> {
+    expected.iter().map(|e| e.name().to_owned()).collect()
+}
+
+/// The score of every scored move, failing when one is outside of 0 to 1
+pub fn scores(expected: &[ExpectedMove]) -> Result<BTreeMap<String, f64>> {
+    expected
+        .iter()
+        .filter_map(|e| match e {
+            ExpectedMove::Move(_) => None,
+            ExpectedMove::Scored { r#move, score } => Some((r#move, *score)),
+        })
+        .map(|(m, score)| {
+            if (0.0..=1.0).contains(&score) {
+                Ok((m.clone(), score))
+            } else {
+                Err(anyhow!(
+                    "The score of \"{}\" is {}, scores go from 0 to 1",
+                    m,
+                    score
+                ))
+            }
+        })
+        .collect()
+}
+
+/// 0 for a failed test, the chosen move's score for a passed one, or 1 when it has none
+pub fn test_score(run: &TestRun) -> f64 {
+    if run.result.is_err() {
+        return 0.0;
+    }
+    run.actual_move
+        .as_ref()
+        .and_then(|m| run.test_case.scores.get(m))
+        .copied()
+        .unwrap_or(1.0)
+}
+
+/// The mean score of the tests that were run, 1 when none were
+pub fn suite_score(results: &[TestRun]) -> f64 {
+    if results.is_empty() {
+        return 1.0;
+    }
+    results.iter().map(test_score).sum::<f64>() / results.len() as f64
+}
+
+/// Whether any of the tests that were run scores its moves
+pub fn has_scores(results: &[TestRun]) -> bool {
+    results.iter().any(|r| !r.test_case.scores.is_empty())
+}
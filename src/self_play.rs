@@ -0,0 +1,87 @@
+//! Survival tests, run with `--self-play`.
+//!
+//! Instead of checking a single move, the game is simulated locally with the
+//! server deciding the move of every snake on the board, until "you" dies or
+//! the turn cap is reached. Opponents with `opponent_moves` follow their script
+//! instead, and are handed back to the server once it runs out.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+
+use crate::board::{Direction, GameState};
+use crate::client::SnakeClient;
+use crate::TestCase;
+
+/// How a self-play game ended for "you"
+pub struct SelfPlayOutcome {
+    /// How many turns "you" survived, at most `max_turns`
+    pub survived: u32,
+    /// The health "you" ended the game with, 0 when it died
+    pub health: i32,
+    /// The board "you" made its last move on, or the final board when it survived
+    pub last_state: GameState,
+    /// The moves "you" made, one per turn
+    pub moves: Vec<Direction>,
+}
+
+pub fn run_self_play(
+    test_case: &TestCase,
+    client: &SnakeClient,
+    url: &str,
+    max_turns: u32,
+) -> Result<SelfPlayOutcome> {
+    let state = GameState::from_value(&test_case.state)?;
+    play(state, &test_case.opponent_moves, client, url, max_turns)
+}
+
+/// Plays the game from the given state until "you" dies or `max_turns` have been played
+pub fn play(
+    mut state: GameState,
+    opponent_moves: &HashMap<String, Vec<Direction>>,
+    client: &SnakeClient,
+    url: &str,
+    max_turns: u32,
+) -> Result<SelfPlayOutcome> {
+    let you = state.you.id.clone();
+    let mut your_moves = vec![];
+
+    for turn in 0..max_turns {
+        let mut moves = HashMap::new();
+        for snake in &state.board.snakes {
+            let scripted = opponent_moves
+                .get(&snake.id)
+                .filter(|_| snake.id != you)
+                .and_then(|script| script.get(turn as usize));
+            if let Some(direction) = scripted {
+                moves.insert(snake.id.clone(), *direction);
+                continue;
+            }
+
+            let perspective = state.as_seen_by(snake).to_value()?;
+            let response = client.request_move(url, &perspective)?;
+            let direction: Direction = response.r#move.parse()?;
+            moves.insert(snake.id.clone(), direction);
+        }
+
+        your_moves.extend(moves.get(&you).copied());
+        let before = state.clone();
+        state.advance(&moves);
+
+        if !state.is_alive(&you) {
+            return Ok(SelfPlayOutcome {
+                survived: turn,
+                health: 0,
+                last_state: before,
+                moves: your_moves,
+            });
+        }
+    }
+
+    Ok(SelfPlayOutcome {
+        survived: max_turns,
+        health: state.you.health,
+        last_state: state,
+        moves: your_moves,
+    })
+}
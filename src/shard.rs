@@ -0,0 +1,42 @@
+//! `--shard-index` and `--shard-count`, splitting the test files between several CI jobs that
+//! each run a slice of the suite.
+
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+
+use crate::Args;
+
+/// The test files of this shard, every `--shard-count`th one starting at `--shard-index`
+///
+/// The files are sorted first, so every job picks the same slice regardless of the order they
+/// were given or discovered in, and the shards together run every file exactly once.
+pub fn select(args: &Args, mut paths: Vec<PathBuf>) -> Result<Vec<PathBuf>> {
+    let (index, count) = match (args.shard_index, args.shard_count) {
+        (Some(index), Some(count)) => (index, count),
+        _ => return Ok(paths),
+    };
+    if index >= count {
+        return Err(anyhow!(
+            "--shard-index is {} but has to be below --shard-count {}, shards are numbered from 0",
+            index,
+            count
+        ));
+    }
+
+    paths.sort();
+    Ok(paths
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| i % count == index)
+        .map(|(_, path)| path)
+        .collect())
+}
+
+/// A `--shard-count`, which has to leave room for at least one shard
+pub fn parse_count(s: &str) -> Result<usize> {
+    match s.parse()? {
+        0 => Err(anyhow!("there has to be at least 1 shard")),
+        count => Ok(count),
+    }
+}
@@ -0,0 +1,40 @@
+//! Matching the `shout` in the server's response against a test's `expected_shout`.
+
+use std::fmt;
+
+use anyhow::Result;
+use regex::Regex;
+
+/// Shouts are matched exactly, unless `expected_shout` starts with `re:` in which
+/// case the rest is a regex that has to match somewhere in the shout
+#[derive(Debug, Clone)]
+pub enum ShoutMatcher {
+    Exact(String),
+    Pattern(Regex),
+}
+
+impl ShoutMatcher {
+    pub fn parse(expected_shout: &str) -> Result<Self> {
+        Ok(match expected_shout.strip_prefix("re:") {
+            Some(pattern) => ShoutMatcher::Pattern(Regex::new(pattern)?),
+            None => ShoutMatcher::Exact(expected_shout.to_owned()),
+        })
+    }
+
+    pub fn matches(&self, shout: Option<&str>) -> bool {
+        let shout = shout.unwrap_or("");
+        match self {
+            ShoutMatcher::Exact(expected) => shout == expected,
+            ShoutMatcher::Pattern(pattern) => pattern.is_match(shout),
+        }
+    }
+}
+
+impl fmt::Display for ShoutMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShoutMatcher::Exact(expected) => write!(f, "\"{}\"", expected),
+            ShoutMatcher::Pattern(pattern) => write!(f, "a match for /{}/", pattern),
+        }
+    }
+}
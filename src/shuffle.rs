@@ -0,0 +1,23 @@
+//! `--shuffle`, running the tests in a random order to catch snakes whose answers depend on
+//! the requests that came before, with the seed printed so the order can be run again.
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::{Args, TestCase};
+
+/// Shuffles the tests with `--shuffle`'s seed, or a random one that is printed when none is given
+///
+/// Without `--shuffle` the tests keep the order they were loaded in, which is sorted by path
+/// for discovered files.
+pub fn shuffle(args: &Args, test_cases: &mut [TestCase]) {
+    let seed = match args.shuffle {
+        Some(Some(seed)) => seed,
+        Some(None) => rand::random(),
+        None => return,
+    };
+    eprintln!(
+        "Shuffled the tests with seed {}, pass --shuffle {} to run them in this order again",
+        seed, seed
+    );
+    test_cases.shuffle(&mut StdRng::seed_from_u64(seed));
+}
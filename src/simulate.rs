@@ -0,0 +1,104 @@
+//! The `simulate` subcommand, which plays a game forward from a single state with the
+//! server moving every snake, and checks that "you" survives long enough.
+//!
+//! It's `--self-play` for a single state, without needing a test file or a suite.
+
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{anyhow, Result};
+use colored::*;
+
+use crate::board::GameState;
+use crate::client::SnakeClient;
+use crate::ruleset;
+use crate::self_play;
+use crate::validate;
+use crate::Args;
+use crate::{diagram, format};
+
+const RULESETS: [&str; 3] = ["standard", "wrapped", "constrictor"];
+
+/// Returns whether "you" survived at least `turns` turns
+///
+/// The file is either a bare game state or a test file, as JSON or YAML, whose `state` or
+/// `diagram` and `opponent_moves` are used.
+pub fn simulate(
+    args: &Args,
+    url: &str,
+    file: &Path,
+    turns: u32,
+    ruleset: Option<&str>,
+) -> Result<bool> {
+    let contents = format::read_value(file)?;
+    let (state, opponent_moves) = if contents.get("board").is_some() {
+        (contents, HashMap::new())
+    } else {
+        let mut test_case_file: crate::TestCaseFile = serde_json::from_value(contents)?;
+        diagram::expand(&mut test_case_file)?;
+        // The declared ruleset fills in a state that doesn't name one, so its rules are simulated
+        let problems = ruleset::apply(&mut test_case_file);
+        if !problems.is_empty() {
+            return Err(anyhow!("The test is invalid: {}", problems.join("; ")));
+        }
+        (
+            test_case_file.state,
+            crate::parse_opponent_moves(&test_case_file.opponent_moves)?,
+        )
+    };
+    // The simulation relies on the state being one the engine could send, ie snakes with bodies
+    let problems = validate::check_state("state", &state);
+    if !problems.is_empty() {
+        return Err(anyhow!(
+            "The state is invalid, `validate` lists every problem: {}",
+            problems.join("; ")
+        ));
+    }
+    let mut state = GameState::from_value(&state)?;
+    if let Some(ruleset) = ruleset {
+        state.game.ruleset.name = ruleset.to_owned();
+    }
+    let name = state.game.ruleset.name.as_str();
+    let name = if name.is_empty() { "standard" } else { name };
+    if !RULESETS.contains(&name) {
+        return Err(anyhow!(
+            "The {} ruleset can't be simulated, pass --ruleset with one of {}",
+            name,
+            RULESETS.join(", ")
+        ));
+    }
+    println!("Simulating up to {} turns of {}", turns, name);
+
+    let client = SnakeClient::new(args)?;
+    let outcome = self_play::play(state, &opponent_moves, &client, url, turns)?;
+
+    let moves: Vec<_> = outcome.moves.iter().map(|d| d.as_str()).collect();
+    println!("Moves: {}", moves.join(" "));
+    if outcome.survived >= turns {
+        println!(
+            "{}",
+            format!(
+                "Survived all {} turns with {} health left",
+                turns, outcome.health
+            )
+            .color(args.expected_color)
+        );
+        return Ok(true);
+    }
+
+    println!(
+        "{}",
+        format!(
+            "Died on turn {}, after surviving {} of {} turns",
+            outcome.last_state.turn + 1,
+            outcome.survived,
+            turns
+        )
+        .color(args.failure_color)
+    );
+    println!(
+        "\nThe board it moved \"{}\" on:\n{}",
+        moves.last().unwrap_or(&"-"),
+        outcome.last_state.render()
+    );
+    Ok(false)
+}
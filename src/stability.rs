@@ -0,0 +1,106 @@
+//! `--stability-runs`, which runs the whole suite several times and reports how
+//! consistently every test passed.
+
+use std::{collections::BTreeMap, fs::write, path::PathBuf, thread, time::Duration};
+
+use anyhow::Result;
+
+use crate::{run_suite, Args};
+
+/// Runs the suite `runs` times, printing a row of outcomes for each test, one column per run
+///
+/// Returns whether every test passed in every run.
+pub fn run_stability(args: &Args, runs: u32) -> Result<bool> {
+    // The outcome of each run, by test, so tests that fail together line up in the same columns
+    let mut matrix: BTreeMap<PathBuf, Vec<&'static str>> = BTreeMap::new();
+    for run in 0..runs {
+        if run > 0 && args.repeat_delay > 0 {
+            thread::sleep(Duration::from_millis(args.repeat_delay));
+        }
+        println!("Run {} of {}", run + 1, runs);
+        let outcome = run_suite(args)?;
+        for (path, result) in outcome.outcomes {
+            let row = matrix.entry(path).or_default();
+            // A test that only loaded in some runs is padded, so the columns stay aligned with the runs
+            row.resize(run as usize, "not_run");
+            row.push(result);
+        }
+    }
+    for row in matrix.values_mut() {
+        row.resize(runs as usize, "not_run");
+    }
+
+    println!("\nOutcomes over {} runs, `.` passed and `F` failed:", runs);
+    let width = matrix
+        .keys()
+        .map(|p| p.display().to_string().len())
+        .max()
+        .unwrap_or(0);
+    let mut passed_runs = 0;
+    let mut stable = 0;
+    for (path, row) in &matrix {
+        let passed = row.iter().filter(|r| **r == "passed").count();
+        let cells: String = row
+            .iter()
+            .map(|r| match *r {
+                "passed" => '.',
+                "not_run" => ' ',
+                _ => 'F',
+            })
+            .collect();
+        let flaky = if passed > 0 && passed < row.len() {
+            "  flaky"
+        } else {
+            stable += 1;
+            ""
+        };
+        println!(
+            "  {:<width$}  {}  {}/{} {:>5.1}%{}",
+            path.display(),
+            cells,
+            passed,
+            runs,
+            percentage(passed, row.len()),
+            flaky,
+            width = width
+        );
+        passed_runs += passed;
+    }
+
+    let total_runs = matrix.len() * runs as usize;
+    println!(
+        "\nStability: {:.1}% of test runs passed, {} of {} tests gave the same outcome every run",
+        percentage(passed_runs, total_runs),
+        stable,
+        matrix.len()
+    );
+
+    if let Some(path) = &args.stability_output {
+        write(path, to_csv(&matrix, runs))?;
+    }
+
+    Ok(passed_runs == total_runs)
+}
+
+/// One row per run and one column per test, with the outcome names in the cells
+fn to_csv(matrix: &BTreeMap<PathBuf, Vec<&'static str>>, runs: u32) -> String {
+    let header: Vec<_> = std::iter::once("run".to_owned())
+        .chain(matrix.keys().map(|p| p.display().to_string()))
+        .collect();
+    let mut csv = header.join(",") + "\n";
+    for run in 0..runs as usize {
+        let row: Vec<_> = std::iter::once((run + 1).to_string())
+            .chain(matrix.values().map(|r| r[run].to_owned()))
+            .collect();
+        csv += &(row.join(",") + "\n");
+    }
+    csv
+}
+
+fn percentage(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / whole as f64
+    }
+}
@@ -0,0 +1,61 @@
+//! The `state_hash` written when a test's expected move is recorded, and the `--check-stale`
+//! report of tests whose state was edited since.
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::{TestCase, TestCaseFile};
+
+/// A hex SHA-256 of the state, which doesn't depend on whitespace or the order of its keys
+pub fn state_hash(state: &Value) -> String {
+    Sha256::digest(state.to_string().as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// The state a file's `state_hash` covers, its last step's when it has `steps`
+///
+/// This is the state as it's written in the file, before `ruleset` or `you_id` fill anything in,
+/// or the one its `diagram` draws when it hasn't been expanded yet.
+pub fn hashed_state(test_case_file: &TestCaseFile) -> Value {
+    if let Some(step) = test_case_file.steps.last() {
+        return step.state.clone();
+    }
+    match &test_case_file.diagram {
+        Some(diagram) if test_case_file.state.is_null() => diagram
+            .to_state(test_case_file.ruleset.as_deref())
+            .unwrap_or_default(),
+        _ => test_case_file.state.clone(),
+    }
+}
+
+/// Whether the state no longer matches the hash stored when its expected move was recorded
+///
+/// Tests without a `state_hash` were never recorded, so they can't be stale.
+pub fn is_stale(state: &Value, recorded: Option<&str>) -> bool {
+    recorded.is_some_and(|hash| hash != state_hash(state))
+}
+
+/// Lists the stale tests, returning whether there were none
+pub fn print_stale(test_cases: &[TestCase]) -> bool {
+    let stale: Vec<_> = test_cases.iter().filter(|t| t.stale).collect();
+    if stale.is_empty() {
+        println!(
+            "The states of all {} tests match their recorded state_hash",
+            test_cases.len()
+        );
+        return true;
+    }
+
+    println!(
+        "{} of {} tests had their state changed since their expected move was recorded:",
+        stale.len(),
+        test_cases.len()
+    );
+    for test_case in stale {
+        println!("  {}", test_case.path.display());
+    }
+    println!("\nCheck their expected moves, then remove their state_hash or re-record them");
+    false
+}
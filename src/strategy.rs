@@ -0,0 +1,58 @@
+//! Built-in heuristics a test can use, through `expected_strategy`, to compute its
+//! expected moves from the board instead of listing them.
+
+use std::str::FromStr;
+
+use anyhow::{anyhow, Result};
+
+use crate::board::{Direction, GameState};
+
+#[derive(Debug, Clone, Copy)]
+pub enum Strategy {
+    /// The safe moves that get closest to the nearest food
+    NearestFood,
+    /// Every move that doesn't run into a wall or a snake body
+    AvoidWalls,
+}
+
+impl FromStr for Strategy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "nearest-food" => Ok(Strategy::NearestFood),
+            "avoid-walls" => Ok(Strategy::AvoidWalls),
+            _ => Err(anyhow!(
+                "Unknown expected_strategy \"{}\", expected nearest-food or avoid-walls",
+                s
+            )),
+        }
+    }
+}
+
+impl Strategy {
+    /// The moves the strategy considers correct, failing when there are none
+    pub fn expected_moves(self, state: &GameState) -> Result<Vec<Direction>> {
+        let safe = state.safe_moves();
+
+        let moves = match self {
+            Strategy::AvoidWalls => safe,
+            Strategy::NearestFood => {
+                let distance_to_food =
+                    |d: &Direction| state.food_distance(state.geometry().step(state.you.head, *d));
+                let best = safe.iter().filter_map(distance_to_food).min();
+                safe.into_iter()
+                    .filter(|d| best.is_some() && distance_to_food(d) == best)
+                    .collect()
+            }
+        };
+
+        if moves.is_empty() {
+            return Err(anyhow!(
+                "The {:?} strategy found no moves that satisfy it in this state",
+                self
+            ));
+        }
+        Ok(moves)
+    }
+}
@@ -0,0 +1,231 @@
+//! `--symmetry`, running every test again on its board rotated and mirrored, with the moves
+//! it checks turned the same way.
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+
+use crate::board::{Coord, Direction};
+use crate::TestCase;
+
+/// A way of turning the board over onto itself, other than leaving it as it is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    /// A quarter turn counterclockwise
+    Rotate90,
+    Rotate180,
+    /// A quarter turn clockwise
+    Rotate270,
+    /// Left and right swapped
+    MirrorX,
+    /// Up and down swapped
+    MirrorY,
+    /// Mirrored along the diagonal from the bottom left corner to the top right one
+    Transpose,
+    /// Mirrored along the diagonal from the top left corner to the bottom right one
+    AntiTranspose,
+}
+
+impl Symmetry {
+    /// The quarter turns and diagonals only map a square board onto itself
+    const SQUARE_ONLY: [Symmetry; 4] = [
+        Symmetry::Rotate90,
+        Symmetry::Rotate270,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+    const ALL: [Symmetry; 7] = [
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::MirrorX,
+        Symmetry::MirrorY,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            Symmetry::Rotate90 => "rotate90",
+            Symmetry::Rotate180 => "rotate180",
+            Symmetry::Rotate270 => "rotate270",
+            Symmetry::MirrorX => "mirror-x",
+            Symmetry::MirrorY => "mirror-y",
+            Symmetry::Transpose => "transpose",
+            Symmetry::AntiTranspose => "anti-transpose",
+        }
+    }
+
+    /// Where a cell of a `width` by `height` board ends up
+    fn coord(self, c: Coord, width: i32, height: i32) -> Coord {
+        let (x, y) = match self {
+            Symmetry::Rotate90 => (height - 1 - c.y, c.x),
+            Symmetry::Rotate180 => (width - 1 - c.x, height - 1 - c.y),
+            Symmetry::Rotate270 => (c.y, width - 1 - c.x),
+            Symmetry::MirrorX => (width - 1 - c.x, c.y),
+            Symmetry::MirrorY => (c.x, height - 1 - c.y),
+            Symmetry::Transpose => (c.y, c.x),
+            Symmetry::AntiTranspose => (height - 1 - c.y, width - 1 - c.x),
+        };
+        Coord { x, y }
+    }
+
+    /// The direction a move ends up pointing in, which doesn't depend on the board's size
+    fn direction(self, direction: Direction) -> Direction {
+        let origin = Coord { x: 0, y: 0 };
+        let from = self.coord(origin, 1, 1);
+        let to = self.coord(origin.moved(direction), 1, 1);
+        Direction::ALL
+            .iter()
+            .copied()
+            .find(|d| from.moved(*d) == to)
+            .expect("a symmetry maps neighbouring cells onto neighbouring cells")
+    }
+
+    /// The same move turned, left unchanged when it isn't a direction
+    fn move_name(self, m: &str) -> String {
+        m.parse::<Direction>()
+            .map(|d| self.direction(d).as_str().to_owned())
+            .unwrap_or_else(|_| m.to_owned())
+    }
+
+    fn moves(self, moves: &mut [String]) {
+        for m in moves {
+            *m = self.move_name(m);
+        }
+    }
+
+    /// Turns every cell of the state, in place
+    ///
+    /// The board keeps its size, the symmetries that would swap its width and height are only
+    /// used on square boards.
+    fn state(self, state: &mut Value) {
+        let (width, height) = match (
+            state["board"]["width"].as_i64(),
+            state["board"]["height"].as_i64(),
+        ) {
+            (Some(width), Some(height)) => (width as i32, height as i32),
+            _ => return,
+        };
+        let cell = |value: &mut Value| {
+            if let (Some(x), Some(y)) = (value["x"].as_i64(), value["y"].as_i64()) {
+                let c = self.coord(
+                    Coord {
+                        x: x as i32,
+                        y: y as i32,
+                    },
+                    width,
+                    height,
+                );
+                value["x"] = c.x.into();
+                value["y"] = c.y.into();
+            }
+        };
+        let snake = |snake: &mut Value| {
+            if let Some(body) = snake.get_mut("body").and_then(Value::as_array_mut) {
+                body.iter_mut().for_each(cell);
+            }
+            if let Some(head) = snake.get_mut("head") {
+                cell(head);
+            }
+        };
+
+        let board = &mut state["board"];
+        if let Some(snakes) = board.get_mut("snakes").and_then(Value::as_array_mut) {
+            snakes.iter_mut().for_each(snake);
+        }
+        for key in ["food", "hazards"] {
+            if let Some(cells) = board.get_mut(key).and_then(Value::as_array_mut) {
+                cells.iter_mut().for_each(cell);
+            }
+        }
+        if let Some(you) = state.get_mut("you") {
+            snake(you);
+        }
+    }
+
+    /// The test on the turned board, named after its file with the symmetry appended
+    fn test_case(self, test_case: &TestCase) -> TestCase {
+        let mut variant = test_case.clone();
+        let size = (test_case.state["board"]["width"].as_i64())
+            .zip(test_case.state["board"]["height"].as_i64());
+
+        self.state(&mut variant.state);
+        for state in &mut variant.lead_in {
+            self.state(state);
+        }
+        for step in &mut variant.steps {
+            self.state(&mut step.state);
+            self.moves(&mut step.expected);
+        }
+        self.moves(&mut variant.expected);
+        self.moves(&mut variant.forbidden);
+        self.moves(&mut variant.preferred);
+        variant.scores = test_case
+            .scores
+            .iter()
+            .map(|(m, score)| (self.move_name(m), *score))
+            .collect();
+        if let Some(ranking) = &mut variant.expected_ranking {
+            self.moves(ranking);
+        }
+        if let Some(m) = variant
+            .expected_response
+            .as_mut()
+            .and_then(|response| response.get_mut("move"))
+        {
+            if let Some(name) = m.as_str().map(|name| self.move_name(name)) {
+                *m = name.into();
+            }
+        }
+        for moves in variant.opponent_moves.values_mut() {
+            for m in moves {
+                *m = self.direction(*m);
+            }
+        }
+        if let (Some(head), Some((width, height))) = (&mut variant.expected_head, size) {
+            *head = self.coord(*head, width as i32, height as i32);
+        }
+
+        variant.path = PathBuf::from(format!("{}#{}", test_case.path.display(), self));
+        variant.symmetry = Some(self);
+        variant
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Every test followed by its rotated and mirrored variants, all seven of them on a square
+/// board and the three that keep its shape otherwise
+pub fn expand(test_cases: Vec<TestCase>) -> Vec<TestCase> {
+    test_cases
+        .into_iter()
+        .flat_map(|test_case| {
+            let square = test_case.state["board"]["width"] == test_case.state["board"]["height"];
+            let variants: Vec<_> = Symmetry::ALL
+                .iter()
+                .filter(|s| square || !Symmetry::SQUARE_ONLY.contains(s))
+                .map(|s| s.test_case(&test_case))
+                .collect();
+            std::iter::once(test_case).chain(variants)
+        })
+        .collect()
+}
+
+/// The file a variant was made from, ie `tests/01.json` for `tests/01.json#rotate90`
+pub fn source_path(test_case: &TestCase) -> &Path {
+    match test_case.symmetry {
+        Some(symmetry) => {
+            let path = test_case.path.to_str().unwrap_or_default();
+            Path::new(path.strip_suffix(&format!("#{}", symmetry)).unwrap_or(path))
+        }
+        None => &test_case.path,
+    }
+}
@@ -0,0 +1,148 @@
+//! Output colors: hex values for `--*-color`, and the `--theme` presets.
+
+use std::env;
+
+use anyhow::{anyhow, Result};
+use colored::Color;
+
+use crate::Args;
+
+/// Presets for the expected, actual and failure colors
+const THEMES: &[(&str, [Color; 3])] = &[
+    ("default", [Color::Yellow, Color::Blue, Color::Red]),
+    (
+        "solarized",
+        [
+            Color::TrueColor {
+                r: 0xb5,
+                g: 0x89,
+                b: 0x00,
+            },
+            Color::TrueColor {
+                r: 0x26,
+                g: 0x8b,
+                b: 0xd2,
+            },
+            Color::TrueColor {
+                r: 0xdc,
+                g: 0x32,
+                b: 0x2f,
+            },
+        ],
+    ),
+    (
+        "colorblind",
+        [
+            Color::TrueColor {
+                r: 0xe6,
+                g: 0x9f,
+                b: 0x00,
+            },
+            Color::TrueColor {
+                r: 0x56,
+                g: 0xb4,
+                b: 0xe9,
+            },
+            Color::TrueColor {
+                r: 0xd5,
+                g: 0x5e,
+                b: 0x00,
+            },
+        ],
+    ),
+];
+
+/// The named colors with their usual terminal RGB values, for falling back from truecolor
+const NAMED: &[(Color, (u8, u8, u8))] = &[
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::White, (229, 229, 229)),
+    (Color::BrightBlack, (127, 127, 127)),
+    (Color::BrightRed, (255, 0, 0)),
+    (Color::BrightGreen, (0, 255, 0)),
+    (Color::BrightYellow, (255, 255, 0)),
+    (Color::BrightBlue, (92, 92, 255)),
+    (Color::BrightMagenta, (255, 0, 255)),
+    (Color::BrightCyan, (0, 255, 255)),
+    (Color::BrightWhite, (255, 255, 255)),
+];
+
+/// Parses a named color like `yellow`, or a `#RRGGBB` hex value
+pub fn parse_color(s: &str) -> Result<Color> {
+    if let Some(hex) = s.strip_prefix('#') {
+        let channel = |i: usize| {
+            hex.get(i..i + 2)
+                .and_then(|c| u8::from_str_radix(c, 16).ok())
+        };
+        return match (hex.len(), channel(0), channel(2), channel(4)) {
+            (6, Some(r), Some(g), Some(b)) => Ok(Color::TrueColor { r, g, b }),
+            _ => Err(anyhow!("Expected a color like #RRGGBB but got \"{}\"", s)),
+        };
+    }
+
+    s.parse()
+        .map_err(|_| anyhow!("Unknown color \"{}\", use a name like yellow or #RRGGBB", s))
+}
+
+pub fn parse_theme(s: &str) -> Result<[Color; 3]> {
+    THEMES
+        .iter()
+        .find(|(name, _)| *name == s)
+        .map(|(_, colors)| *colors)
+        .ok_or_else(|| {
+            let names: Vec<_> = THEMES.iter().map(|(name, _)| *name).collect();
+            anyhow!(
+                "Unknown theme \"{}\", expected one of {}",
+                s,
+                names.join(", ")
+            )
+        })
+}
+
+/// Applies `--theme`, then falls back to named colors when the terminal has no truecolor support
+pub fn apply(args: &mut Args) {
+    if let Some([expected, actual, failure]) = args.theme {
+        args.expected_color = expected;
+        args.actual_color = actual;
+        args.failure_color = failure;
+    }
+
+    if !supports_truecolor() {
+        for color in [
+            &mut args.expected_color,
+            &mut args.actual_color,
+            &mut args.failure_color,
+        ] {
+            *color = nearest_named(*color);
+        }
+    }
+}
+
+fn supports_truecolor() -> bool {
+    matches!(
+        env::var("COLORTERM").as_deref(),
+        Ok("truecolor") | Ok("24bit")
+    )
+}
+
+fn nearest_named(color: Color) -> Color {
+    let (r, g, b) = match color {
+        Color::TrueColor { r, g, b } => (r, g, b),
+        named => return named,
+    };
+    let distance = |(nr, ng, nb): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(r, nr) + d(g, ng) + d(b, nb)
+    };
+
+    NAMED
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb))
+        .map(|(named, _)| *named)
+        .unwrap()
+}
@@ -0,0 +1,76 @@
+//! Declarative changes to the request body, for servers that don't accept the
+//! bare game state the Battlesnake API sends.
+
+use anyhow::{anyhow, Result};
+use serde_json::{Map, Value};
+
+use crate::Args;
+
+#[derive(Debug, Clone)]
+pub enum Transform {
+    /// Adds a JSON value to the top level of the state, before it is wrapped
+    InjectField(String, Value),
+    /// Nests the body under the given key, ie `{"game_state": <state>}`
+    Wrap(String),
+    /// Adds a constant string field to the top level of the body
+    AddField(String, String),
+}
+
+pub fn from_args(args: &Args) -> Vec<Transform> {
+    let mut transforms = vec![];
+
+    for (key, value) in &args.inject_field {
+        transforms.push(Transform::InjectField(key.clone(), value.clone()));
+    }
+    if let Some(key) = &args.wrap_key {
+        transforms.push(Transform::Wrap(key.clone()));
+    }
+    for (key, value) in &args.add_field {
+        transforms.push(Transform::AddField(key.clone(), value.clone()));
+    }
+
+    transforms
+}
+
+/// Applies the transforms in order, without the flags this returns the state unchanged
+pub fn apply(transforms: &[Transform], state: &Value) -> Value {
+    let mut body = state.clone();
+
+    for t in transforms {
+        match t {
+            Transform::InjectField(key, value) => {
+                if let Value::Object(map) = &mut body {
+                    map.insert(key.clone(), value.clone());
+                }
+            }
+            Transform::Wrap(key) => {
+                let mut envelope = Map::new();
+                envelope.insert(key.clone(), body);
+                body = Value::Object(envelope);
+            }
+            Transform::AddField(key, value) => {
+                if let Value::Object(map) = &mut body {
+                    map.insert(key.clone(), Value::String(value.clone()));
+                }
+            }
+        }
+    }
+
+    body
+}
+
+/// Parses a `key=value` command line argument
+pub fn parse_key_value(s: &str) -> Result<(String, String)> {
+    let (key, value) = s
+        .split_once('=')
+        .ok_or_else(|| anyhow!("Expected key=value but got \"{}\"", s))?;
+    Ok((key.to_owned(), value.to_owned()))
+}
+
+/// Parses a `key=json-value` command line argument, ie `metadata={"source": "tests"}`
+pub fn parse_key_json(s: &str) -> Result<(String, Value)> {
+    let (key, value) = parse_key_value(s)?;
+    let value = serde_json::from_str(&value)
+        .map_err(|e| anyhow!("The value of \"{}\" is not valid JSON: {}", key, e))?;
+    Ok((key, value))
+}
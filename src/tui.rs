@@ -0,0 +1,266 @@
+//! `--tui`, a full screen explorer of the results with the tests listed on the left and the
+//! details of the selected one on the right, for suites too big to scroll through.
+//!
+//! It's drawn with plain ANSI escapes, with the terminal put into raw mode by `stty`, so it
+//! only works on Unix terminals.
+
+use std::{
+    io::{self, IsTerminal, Read, Write},
+    process::{Command, Stdio},
+};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::board::GameState;
+use crate::client::SnakeClient;
+use crate::{execute_test, html::expected_moves, lifecycle::Lifecycle, Args, TestRun};
+
+const HELP: &str =
+    "j/k move  f next failure  u/d scroll details  r re-run  o open in $EDITOR  q quit";
+
+/// The terminal in raw mode on the alternate screen, put back the way it was when dropped
+struct Screen {
+    saved: String,
+}
+
+impl Screen {
+    fn enter() -> Result<Screen> {
+        let saved = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+        print!("\x1b[?1049h\x1b[?25l");
+        io::stdout().flush()?;
+        Ok(Screen {
+            saved: saved.trim().to_owned(),
+        })
+    }
+
+    /// Rows and columns, 24 by 80 when `stty` can't tell
+    fn size() -> (usize, usize) {
+        stty(&["size"])
+            .ok()
+            .and_then(|size| {
+                let mut parts = size.split_whitespace().map(|n| n.parse().ok());
+                Some((parts.next()??, parts.next()??))
+            })
+            .unwrap_or((24, 80))
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = io::stdout().flush();
+        let _ = stty(&[self.saved.as_str()]);
+    }
+}
+
+fn stty(args: &[&str]) -> Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()
+        .context("Could not run stty")?;
+    if !output.status.success() {
+        return Err(anyhow!("stty {} failed", args.join(" ")));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+enum Key {
+    Up,
+    Down,
+    Char(u8),
+}
+
+fn read_key(stdin: &mut impl Read) -> Result<Key> {
+    let mut byte = [0];
+    stdin.read_exact(&mut byte)?;
+    if byte[0] != 0x1b {
+        return Ok(Key::Char(byte[0]));
+    }
+    // Arrow keys arrive as `ESC [ A` and `ESC [ B`
+    let mut sequence = [0; 2];
+    stdin.read_exact(&mut sequence)?;
+    Ok(match sequence {
+        [b'[', b'A'] => Key::Up,
+        [b'[', b'B'] => Key::Down,
+        _ => Key::Char(0),
+    })
+}
+
+/// Shows the results until `q` is pressed, with the tests that were re-run replaced in `results`
+pub fn explore(
+    args: &Args,
+    client: &SnakeClient,
+    url: &str,
+    lifecycle: &Lifecycle,
+    results: &mut [TestRun],
+) -> Result<()> {
+    if !cfg!(unix) || !io::stdin().is_terminal() || !io::stdout().is_terminal() {
+        return Err(anyhow!("--tui needs a Unix terminal for stdin and stdout"));
+    }
+    if results.is_empty() {
+        return Ok(());
+    }
+
+    let colorized = colored::control::SHOULD_COLORIZE.should_colorize();
+    colored::control::set_override(false);
+    let explored = run(args, client, url, lifecycle, results);
+    colored::control::set_override(colorized);
+    explored
+}
+
+fn run(
+    args: &Args,
+    client: &SnakeClient,
+    url: &str,
+    lifecycle: &Lifecycle,
+    results: &mut [TestRun],
+) -> Result<()> {
+    let mut screen = Some(Screen::enter()?);
+    let mut stdin = io::stdin();
+    // Start on the first failure, that's what there is to look at
+    let mut selected = results.iter().position(|r| r.result.is_err()).unwrap_or(0);
+    let mut scroll = 0;
+    let mut status = String::new();
+
+    loop {
+        draw(args, results, selected, scroll, &status)?;
+        status.clear();
+        match read_key(&mut stdin)? {
+            Key::Up | Key::Char(b'k') => {
+                selected = selected.saturating_sub(1);
+                scroll = 0;
+            }
+            Key::Down | Key::Char(b'j') => {
+                selected = (selected + 1).min(results.len() - 1);
+                scroll = 0;
+            }
+            Key::Char(b'f') => {
+                let next = (1..=results.len())
+                    .map(|i| (selected + i) % results.len())
+                    .find(|i| results[*i].result.is_err());
+                match next {
+                    Some(next) => selected = next,
+                    None => status = "No test failed".to_owned(),
+                }
+                scroll = 0;
+            }
+            Key::Char(b'd') => scroll += Screen::size().0 / 2,
+            Key::Char(b'u') => scroll = scroll.saturating_sub(Screen::size().0 / 2),
+            Key::Char(b'r') => {
+                let test_case = results[selected].test_case.clone();
+                results[selected] = execute_test(args, client, url, lifecycle, test_case);
+                status = match &results[selected].result {
+                    Ok(()) => "Re-ran, passed".to_owned(),
+                    Err(failure) => format!("Re-ran, {}", failure.outcome()),
+                };
+            }
+            Key::Char(b'o') => {
+                let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_owned());
+                let path = crate::symmetry::source_path(&results[selected].test_case).to_owned();
+                // The editor gets the terminal back the way it was
+                drop(screen.take());
+                let opened = Command::new(&editor).arg(&path).status();
+                screen = Some(Screen::enter()?);
+                if let Err(e) = opened {
+                    status = format!("Could not run {}: {}", editor, e);
+                }
+            }
+            Key::Char(b'q') | Key::Char(3) => break,
+            Key::Char(_) => {}
+        }
+    }
+    drop(screen);
+    Ok(())
+}
+
+fn draw(
+    args: &Args,
+    results: &[TestRun],
+    selected: usize,
+    scroll: usize,
+    status: &str,
+) -> Result<()> {
+    let (rows, columns) = Screen::size();
+    let height = rows.saturating_sub(1).max(1);
+    let left = (columns / 3).clamp(10, 50);
+    let right = columns.saturating_sub(left + 3);
+
+    // The list scrolls to keep the selected test in view
+    let first = selected.saturating_sub(height - 1);
+    let details = details(args, &results[selected]);
+    let mut frame = "\x1b[H\x1b[2J".to_owned();
+    for row in 0..height {
+        let entry = match results.get(first + row) {
+            Some(r) => {
+                let (marker, color) = match r.result {
+                    Ok(()) => ("✓", "\x1b[32m"),
+                    Err(_) => ("✗", "\x1b[31m"),
+                };
+                let name = fit(&r.test_case.path.display().to_string(), left - 2);
+                let text = format!(
+                    "{}{}\x1b[0m {:<width$}",
+                    color,
+                    marker,
+                    name,
+                    width = left - 2
+                );
+                if first + row == selected {
+                    format!("\x1b[7m{}\x1b[0m", text)
+                } else {
+                    text
+                }
+            }
+            None => " ".repeat(left),
+        };
+        let detail = details.get(scroll + row).map_or("", String::as_str);
+        frame += &format!("{} │ {}\r\n", entry, fit(detail, right));
+    }
+    let footer = if status.is_empty() { HELP } else { status };
+    frame += &format!(
+        "\x1b[7m{:<width$}\x1b[0m",
+        fit(footer, columns),
+        width = columns
+    );
+
+    print!("{}", frame);
+    io::stdout().flush()?;
+    Ok(())
+}
+
+/// The text cut to `width` characters
+fn fit(text: &str, width: usize) -> String {
+    text.chars().take(width).collect()
+}
+
+/// Everything shown about a test, a line at a time
+fn details(args: &Args, r: &TestRun) -> Vec<String> {
+    let test_case = &r.test_case;
+    let mut text = format!("{}\n", test_case.path.display());
+    if let Some(description) = &test_case.description {
+        text += &format!("{}\n", description);
+    }
+    text += &match &r.result {
+        Ok(()) => "\nPassed\n".to_owned(),
+        Err(failure) => format!("\n{}\n", failure.display_failure(args)),
+    };
+    text += &format!(
+        "\nExpected: {}\nActual: {}\nLatency: {}\n",
+        expected_moves(test_case),
+        r.actual_move.as_deref().unwrap_or("none"),
+        r.duration
+            .map(|d| args.latency(d))
+            .unwrap_or_else(|| "none".to_owned())
+    );
+    if let Ok(state) = GameState::from_value(&test_case.state) {
+        text += &format!("\n{}", state.render());
+    }
+    if let Some(response) = &r.response {
+        text += &format!(
+            "\nResponse:\n{}\n",
+            serde_json::to_string_pretty(response).unwrap_or_default()
+        );
+    }
+    text.lines().map(str::to_owned).collect()
+}
@@ -0,0 +1,61 @@
+//! How latencies are printed, in the unit given with `--latency-unit`.
+
+use std::{str::FromStr, time::Duration};
+
+use anyhow::{anyhow, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatencyUnit {
+    Microseconds,
+    Milliseconds,
+    Seconds,
+}
+
+impl FromStr for LatencyUnit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "us" => Ok(LatencyUnit::Microseconds),
+            "ms" => Ok(LatencyUnit::Milliseconds),
+            "s" => Ok(LatencyUnit::Seconds),
+            _ => Err(anyhow!(
+                "Unknown latency unit \"{}\", expected ms, us or s",
+                s
+            )),
+        }
+    }
+}
+
+impl LatencyUnit {
+    pub fn suffix(self) -> &'static str {
+        match self {
+            LatencyUnit::Microseconds => "us",
+            LatencyUnit::Milliseconds => "ms",
+            LatencyUnit::Seconds => "s",
+        }
+    }
+
+    /// Whole microseconds and milliseconds are precise enough, seconds need a few decimals
+    fn default_precision(self) -> usize {
+        match self {
+            LatencyUnit::Microseconds | LatencyUnit::Milliseconds => 0,
+            LatencyUnit::Seconds => 3,
+        }
+    }
+
+    /// The latency with the unit's suffix, ie `12ms`, using the unit's default precision when none is given
+    pub fn format(self, latency: Duration, precision: Option<usize>) -> String {
+        let value = match self {
+            LatencyUnit::Microseconds => latency.as_secs_f64() * 1_000_000.0,
+            LatencyUnit::Milliseconds => latency.as_secs_f64() * 1000.0,
+            LatencyUnit::Seconds => latency.as_secs_f64(),
+        };
+        format!(
+            "{:.*}{}",
+            precision.unwrap_or_else(|| self.default_precision()),
+            value,
+            self.suffix()
+        )
+    }
+}
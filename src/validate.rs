@@ -0,0 +1,382 @@
+//! Checking that the states of test files are game states the engine could send, before any of
+//! them is sent to a server, and the `validate` subcommand that reports every problem found.
+
+use std::collections::HashSet;
+
+use anyhow::Result;
+use colored::Colorize;
+use serde_json::{Map, Value};
+
+use crate::api::type_name;
+use crate::board::{Coord, Direction, GameState};
+use crate::{diagram, format, inject_you, ruleset, test_file_paths, Args, TestCaseFile};
+
+/// Every problem with the states of a test file, each starting with the field it's about, ie
+/// `steps[1].state.board.snakes[0].head`
+///
+/// `state`, every one of the `steps` and every `lead_in` state is checked, after `you_id` fills
+/// in `you`.
+pub fn check_file(test_case_file: &TestCaseFile) -> Vec<String> {
+    let states = std::iter::once(("state".to_owned(), &test_case_file.state))
+        .filter(|(_, state)| !state.is_null())
+        .chain(
+            test_case_file
+                .steps
+                .iter()
+                .enumerate()
+                .map(|(i, step)| (format!("steps[{}].state", i), &step.state)),
+        )
+        .chain(
+            test_case_file
+                .lead_in
+                .iter()
+                .enumerate()
+                .map(|(i, state)| (format!("lead_in[{}]", i), state)),
+        );
+
+    let mut problems = vec![];
+    for (path, state) in states {
+        let mut state = state.clone();
+        if let Some(you_id) = &test_case_file.you_id {
+            if let Err(e) = inject_you(&mut state, you_id) {
+                problems.push(format!("{}: {}", path, e));
+                continue;
+            }
+        }
+        problems.extend(check_state(&path, &state));
+    }
+    problems
+}
+
+/// The problems with a single game state, `path` being where it is in the test file
+pub fn check_state(path: &str, state: &Value) -> Vec<String> {
+    let mut checker = Checker::default();
+    checker.game_state(path, state);
+    if !checker.problems.is_empty() {
+        return checker.problems;
+    }
+
+    // Every field the typed state needs was checked above, so this only fails on values that
+    // don't fit, like a width larger than an i32
+    match GameState::from_value(state) {
+        Ok(game_state) => check_consistency(path, state, &game_state),
+        Err(e) => vec![format!("{}: {}", path, e)],
+    }
+}
+
+/// Collects a problem for every field that is missing or has the wrong type
+#[derive(Default)]
+struct Checker {
+    problems: Vec<String>,
+}
+
+impl Checker {
+    fn wrong_type(&mut self, path: &str, expected: &str, value: &Value) {
+        self.problems.push(format!(
+            "{}: expected {} but found {}",
+            path,
+            expected,
+            type_name(value)
+        ));
+    }
+
+    fn object<'a>(&mut self, path: &str, value: &'a Value) -> Option<&'a Map<String, Value>> {
+        let object = value.as_object();
+        if object.is_none() {
+            self.wrong_type(path, "an object", value);
+        }
+        object
+    }
+
+    fn required<'a>(
+        &mut self,
+        path: &str,
+        object: &'a Map<String, Value>,
+        key: &str,
+    ) -> Option<(String, &'a Value)> {
+        let field = format!("{}.{}", path, key);
+        match object.get(key) {
+            Some(value) => Some((field, value)),
+            None => {
+                self.problems.push(format!("{}: is missing", field));
+                None
+            }
+        }
+    }
+
+    fn optional<'a>(
+        &mut self,
+        path: &str,
+        object: &'a Map<String, Value>,
+        key: &str,
+    ) -> Option<(String, &'a Value)> {
+        object
+            .get(key)
+            .map(|value| (format!("{}.{}", path, key), value))
+    }
+
+    fn integer(&mut self, path: &str, value: &Value) {
+        if !value.is_i64() {
+            self.wrong_type(path, "an integer", value);
+        }
+    }
+
+    fn string(&mut self, path: &str, value: &Value) {
+        if !value.is_string() {
+            self.wrong_type(path, "a string", value);
+        }
+    }
+
+    fn array<'a>(&mut self, path: &str, value: &'a Value) -> &'a [Value] {
+        match value.as_array() {
+            Some(items) => items,
+            None => {
+                self.wrong_type(path, "an array", value);
+                &[]
+            }
+        }
+    }
+
+    fn game_state(&mut self, path: &str, state: &Value) {
+        let state = match self.object(path, state) {
+            Some(state) => state,
+            None => return,
+        };
+
+        if let Some((path, game)) = self.required(path, state, "game") {
+            if let Some(game) = self.object(&path, game) {
+                if let Some((path, id)) = self.required(&path, game, "id") {
+                    self.string(&path, id);
+                }
+                if let Some((path, ruleset)) = self.optional(&path, game, "ruleset") {
+                    if let Some(ruleset) = self.object(&path, ruleset) {
+                        if let Some((path, name)) = self.optional(&path, ruleset, "name") {
+                            self.string(&path, name);
+                        }
+                    }
+                }
+                if let Some((path, timeout)) = self.optional(&path, game, "timeout") {
+                    self.integer(&path, timeout);
+                }
+            }
+        }
+        if let Some((path, turn)) = self.required(path, state, "turn") {
+            if !turn.is_u64() {
+                self.wrong_type(&path, "a non-negative integer", turn);
+            }
+        }
+        if let Some((path, board)) = self.required(path, state, "board") {
+            self.board(&path, board);
+        }
+        if let Some((path, you)) = self.required(path, state, "you") {
+            self.snake(&path, you);
+        }
+    }
+
+    fn board(&mut self, path: &str, board: &Value) {
+        let board = match self.object(path, board) {
+            Some(board) => board,
+            None => return,
+        };
+
+        for key in ["width", "height"] {
+            if let Some((path, size)) = self.required(path, board, key) {
+                self.integer(&path, size);
+            }
+        }
+        for key in ["food", "hazards"] {
+            if let Some((path, cells)) = self.optional(path, board, key) {
+                for (i, cell) in self.array(&path, cells).iter().enumerate() {
+                    self.coord(&format!("{}[{}]", path, i), cell);
+                }
+            }
+        }
+        if let Some((path, snakes)) = self.required(path, board, "snakes") {
+            for (i, snake) in self.array(&path, snakes).iter().enumerate() {
+                self.snake(&format!("{}[{}]", path, i), snake);
+            }
+        }
+    }
+
+    fn snake(&mut self, path: &str, snake: &Value) {
+        let snake = match self.object(path, snake) {
+            Some(snake) => snake,
+            None => return,
+        };
+
+        if let Some((path, id)) = self.required(path, snake, "id") {
+            self.string(&path, id);
+        }
+        if let Some((path, name)) = self.optional(path, snake, "name") {
+            self.string(&path, name);
+        }
+        if let Some((path, health)) = self.required(path, snake, "health") {
+            self.integer(&path, health);
+        }
+        if let Some((path, body)) = self.required(path, snake, "body") {
+            for (i, cell) in self.array(&path, body).iter().enumerate() {
+                self.coord(&format!("{}[{}]", path, i), cell);
+            }
+        }
+        if let Some((path, head)) = self.required(path, snake, "head") {
+            self.coord(&path, head);
+        }
+        if let Some((path, length)) = self.optional(path, snake, "length") {
+            if !length.is_u64() {
+                self.wrong_type(&path, "a non-negative integer", length);
+            }
+        }
+    }
+
+    fn coord(&mut self, path: &str, cell: &Value) {
+        if let Some(cell) = self.object(path, cell) {
+            for key in ["x", "y"] {
+                if let Some((path, value)) = self.required(path, cell, key) {
+                    self.integer(&path, value);
+                }
+            }
+        }
+    }
+}
+
+/// The problems a state with every field in place can still have: an empty board, snakes whose
+/// bodies don't hold together, and a `you` that isn't the snake on the board
+fn check_consistency(path: &str, raw: &Value, state: &GameState) -> Vec<String> {
+    let mut problems = vec![];
+    let board = &state.board;
+
+    for (key, size) in [("width", board.width), ("height", board.height)] {
+        if size < 1 {
+            problems.push(format!(
+                "{}.board.{}: is {}, but a board is at least 1 cell across",
+                path, key, size
+            ));
+        }
+    }
+
+    let geometry = state.geometry();
+    let snakes = board
+        .snakes
+        .iter()
+        .enumerate()
+        .map(|(i, snake)| {
+            (
+                format!("{}.board.snakes[{}]", path, i),
+                snake,
+                &raw["board"]["snakes"][i],
+            )
+        })
+        .chain(std::iter::once((
+            format!("{}.you", path),
+            &state.you,
+            &raw["you"],
+        )));
+    for (path, snake, raw_snake) in snakes {
+        let head = match snake.body.first() {
+            Some(head) => *head,
+            None => {
+                problems.push(format!("{}.body: is empty", path));
+                continue;
+            }
+        };
+        if snake.head != head {
+            problems.push(format!(
+                "{}.head: is {} but the first cell of the body is {}",
+                path, snake.head, head
+            ));
+        }
+        if raw_snake.get("length").is_some() && snake.length != snake.body.len() {
+            problems.push(format!(
+                "{}.length: is {} but the body has {} cells",
+                path,
+                snake.length,
+                snake.body.len()
+            ));
+        }
+        // Cells may repeat, a snake's tail is stacked at the start of a game and after eating
+        for (i, pair) in snake.body.windows(2).enumerate() {
+            let touching = |a: Coord, b: Coord| {
+                a == b || Direction::ALL.iter().any(|d| geometry.step(a, *d) == b)
+            };
+            if !touching(pair[0], pair[1]) {
+                problems.push(format!(
+                    "{}.body[{}]: {} isn't next to the cell before it, {}",
+                    path,
+                    i + 1,
+                    pair[1],
+                    pair[0]
+                ));
+            }
+        }
+    }
+
+    let mut ids = HashSet::new();
+    for (i, snake) in board.snakes.iter().enumerate() {
+        if !ids.insert(&snake.id) {
+            problems.push(format!(
+                "{}.board.snakes[{}].id: \"{}\" is used by another snake too",
+                path, i, snake.id
+            ));
+        }
+    }
+
+    match board.snakes.iter().position(|s| s.id == state.you.id) {
+        None => problems.push(format!(
+            "{}.you.id: \"{}\" is not the id of any snake in board.snakes",
+            path, state.you.id
+        )),
+        Some(i) => {
+            let on_board = &board.snakes[i];
+            if on_board.body != state.you.body || on_board.health != state.you.health {
+                problems.push(format!(
+                    "{}.you: doesn't match board.snakes[{}], the same snake on the board",
+                    path, i
+                ));
+            }
+        }
+    }
+
+    problems
+}
+
+/// The `validate` subcommand, printing the problems with every test file and returning whether
+/// there were none
+pub fn validate(args: &Args) -> Result<bool> {
+    let paths = test_file_paths(args)?;
+    let mut invalid = 0;
+
+    for path in &paths {
+        let problems = match format::read_test_case_file(path) {
+            Ok(mut test_case_file) => match diagram::expand(&mut test_case_file) {
+                Ok(()) => {
+                    let mut problems = ruleset::apply(&mut test_case_file);
+                    problems.extend(check_file(&test_case_file));
+                    problems
+                }
+                Err(e) => vec![format!("{:#}", e)],
+            },
+            Err(e) => vec![format!("Could not be read: {:#}", e)],
+        };
+        if problems.is_empty() {
+            continue;
+        }
+        invalid += 1;
+
+        println!(
+            "{}: {}",
+            "Invalid test".color(args.failure_color),
+            path.display()
+        );
+        for problem in problems {
+            println!("  - {}", problem);
+        }
+        println!();
+    }
+
+    if invalid == 0 {
+        println!("{} test files are valid", paths.len());
+    } else {
+        println!("{} of {} test files are invalid", invalid, paths.len());
+    }
+    Ok(invalid == 0)
+}
@@ -0,0 +1,148 @@
+//! `--watch`, which runs the suite again whenever a test file or a `--watch-path` changes.
+
+use std::{
+    collections::BTreeMap,
+    io::{self, IsTerminal, Write},
+    path::{Component, Path, PathBuf},
+    thread,
+    time::Duration,
+    time::SystemTime,
+};
+
+use anyhow::Result;
+use glob::glob;
+use log::{debug, warn};
+
+use crate::{notify, run_suite, Args};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Runs the suite, then again after every change, until interrupted
+///
+/// When only test files changed just those are run again, a change to anything else, ie the
+/// snake's binary passed with `--watch-path`, runs the whole suite. Changes are found by
+/// polling modification times, which needs no platform specific file watching.
+pub fn watch(args: &Args) -> Result<()> {
+    let mut last_seen = modification_times(args);
+    let mut changed_tests = vec![];
+    loop {
+        clear_screen();
+        let outcome = if changed_tests.is_empty() {
+            run_suite(args)
+        } else {
+            run_suite(&Args {
+                files: changed_tests.clone(),
+                ..args.clone()
+            })
+        };
+        match outcome {
+            Ok(outcome) if args.notify => notify::notify_outcome(&outcome),
+            Ok(_) => {}
+            // A broken test file shouldn't end the watch, the next save can fix it
+            Err(e) => println!("{:#}", e),
+        }
+        if changed_tests.is_empty() {
+            println!("\nWatching {} for changes...", watched(args));
+        } else {
+            println!(
+                "\nRan the {} changed test files, watching {} for changes...",
+                changed_tests.len(),
+                watched(args)
+            );
+        }
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let seen = modification_times(args);
+            if seen != last_seen {
+                let changed = changed_paths(&last_seen, &seen);
+                debug!("Changes detected: {:?}", changed);
+                changed_tests = if changed.iter().all(|path| is_test_file(args, path)) {
+                    changed
+                } else {
+                    vec![]
+                };
+                last_seen = seen;
+                break;
+            }
+        }
+    }
+}
+
+/// The test directory and the `--watch-path`s, for the message printed between runs
+fn watched(args: &Args) -> String {
+    std::iter::once(args.test_directory.clone())
+        .chain(args.watch_path.iter().map(|p| p.display().to_string()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Clears the terminal so every run starts at the top, nothing when the output is redirected
+fn clear_screen() {
+    if io::stdout().is_terminal() {
+        print!("\x1B[2J\x1B[H");
+        let _ = io::stdout().flush();
+    }
+}
+
+/// The paths that were added, modified or removed
+fn changed_paths(
+    before: &BTreeMap<PathBuf, SystemTime>,
+    after: &BTreeMap<PathBuf, SystemTime>,
+) -> Vec<PathBuf> {
+    let mut changed: Vec<_> = after
+        .iter()
+        .filter(|(path, modified)| before.get(*path) != Some(modified))
+        .map(|(path, _)| path.clone())
+        .collect();
+    changed.extend(before.keys().filter(|p| !after.contains_key(*p)).cloned());
+    changed
+}
+
+/// Whether the path is a test file that still exists in the test directory, so it can be run by itself
+fn is_test_file(args: &Args, path: &Path) -> bool {
+    let extension = path.to_string_lossy();
+    path.is_file()
+        && without_dots(path).starts_with(without_dots(Path::new(&args.test_directory)))
+        && args
+            .extensions()
+            .iter()
+            .any(|e| extension.ends_with(&format!(".{}", e)))
+}
+
+/// The path without `.` components, as glob leaves out the `./` a test directory often starts with
+fn without_dots(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| *c != Component::CurDir)
+        .collect()
+}
+
+fn modification_times(args: &Args) -> BTreeMap<PathBuf, SystemTime> {
+    let mut times = directory_modification_times(&args.test_directory);
+    for path in &args.watch_path {
+        if path.is_dir() {
+            times.extend(directory_modification_times(&path.to_string_lossy()));
+        } else if let Ok(modified) = path.metadata().and_then(|m| m.modified()) {
+            times.insert(path.clone(), modified);
+        }
+    }
+    times
+}
+
+fn directory_modification_times(directory: &str) -> BTreeMap<PathBuf, SystemTime> {
+    let pattern = format!("{}/**/*", directory.trim_end_matches('/'));
+    let entries = match glob(&pattern) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!("Could not watch {}: {}", directory, e);
+            return BTreeMap::new();
+        }
+    };
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|path| {
+            let modified = path.metadata().and_then(|m| m.modified()).ok()?;
+            Some((path, modified))
+        })
+        .collect()
+}